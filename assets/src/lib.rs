@@ -1,4 +1,8 @@
+#![no_std]
+
 pub mod constants;
 pub mod heads_up;
+#[allow(clippy::large_const_arrays)]
 pub mod lookup;
+#[allow(clippy::large_const_arrays)]
 pub mod offsets;
@@ -1,4 +1,4 @@
-pub const LOOKUP: [u16; 73775] = [
+pub const LOOKUP: [u16; 73776] = [
     6499, 4252, 24585, 5820, 12945, 24603, 24581, 24681, 5820, 8994, 8289, 8288, 8377, 8754, 5415,
     8993, 8299, 6288, 6279, 12341, 28681, 28766, 28765, 6280, 12869, 6728, 6508, 24696, 12926,
     6161, 8377, 6509, 13006, 12946, 8298, 8371, 24684, 8246, 8599, 24615, 8371, 12940, 6476, 6719,
@@ -5019,10 +5019,10 @@ pub const LOOKUP: [u16; 73775] = [
     28767, 24668, 24667, 24666, 24667, 24670, 24666, 24689, 13141, 13075, 6732, 6283, 38, 12943,
     28672, 28674, 9045, 12924, 28786, 24604, 28766, 24628, 28766, 13075, 24689, 12288, 28674,
     28673, 28684, 28684, 28684, 28822, 28822, 28672, 13114, 13114, 24588, 8980, 24588, 28672,
-    28764, 6715, 13122, 13141, 5404, 13048, 8851, 24576, 8851, 5195, 13114, 12682, 24719,
+    28764, 6715, 13122, 13141, 5404, 13048, 8851, 24576, 8851, 5195, 13114, 12682, 24719, 0,
 ];
 
-pub const LOOKUP_FLUSH: [u16; 8129] = [
+pub const LOOKUP_FLUSH: [u16; 8130] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     32769, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20480, 0, 0, 0, 0, 0, 0, 0, 20481, 0, 0, 0,
     20482, 0, 20483, 32770, 32770, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20484, 0, 0, 0, 0,
@@ -5488,5 +5488,5 @@ pub const LOOKUP_FLUSH: [u16; 8129] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32777, 32777, 32777, 0,
     32777, 0, 0, 0, 32777, 0, 0, 0, 0, 0, 0, 0, 32777, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     32777, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 32777,
+    0, 0, 32777, 0,
 ];
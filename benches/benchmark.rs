@@ -1,6 +1,11 @@
+#![allow(clippy::needless_range_loop)]
+
 use assets::constants::*;
 use criterion::{criterion_group, criterion_main, Criterion};
-use holdem_hand_evaluator::Hand;
+use holdem_hand_evaluator::{
+    evaluate_batch, evaluate_batch_soa, evaluate_x8, BoardEvaluator, Hand,
+};
+use std::convert::TryInto;
 
 fn bench() -> u16 {
     let mut sum: u16 = 0;
@@ -30,8 +35,137 @@ fn bench() -> u16 {
     sum
 }
 
+/// Returns all 7-card hands that contain the first two cards (2,118,760 hands).
+fn batch_hands() -> Vec<Hand> {
+    let mut hands = Vec::new();
+    let hand = Hand::new().add_card(0).add_card(1);
+    for k in 2..(NUMBER_OF_CARDS - 4) {
+        let hand = hand.add_card(k);
+        for m in (k + 1)..(NUMBER_OF_CARDS - 3) {
+            let hand = hand.add_card(m);
+            for n in (m + 1)..(NUMBER_OF_CARDS - 2) {
+                let hand = hand.add_card(n);
+                for p in (n + 1)..(NUMBER_OF_CARDS - 1) {
+                    let hand = hand.add_card(p);
+                    for q in (p + 1)..NUMBER_OF_CARDS {
+                        hands.push(hand.add_card(q));
+                    }
+                }
+            }
+        }
+    }
+    hands
+}
+
+/// Enumerates all boards of AsKs vs. QhQd by evaluating both 7-card hands from scratch.
+fn bench_runouts_naive() -> (u32, u32, u32) {
+    let hand1: Hand = "AsKs".parse().unwrap();
+    let hand2: Hand = "QhQd".parse().unwrap();
+    let alive_cards = (0..NUMBER_OF_CARDS)
+        .filter(|&c| !(hand1 + hand2).contains(c))
+        .collect::<Vec<_>>();
+    let len = alive_cards.len();
+    let mut count = (0, 0, 0);
+    for i in 0..(len - 4) {
+        let hand1 = hand1.add_card(alive_cards[i]);
+        let hand2 = hand2.add_card(alive_cards[i]);
+        for j in (i + 1)..(len - 3) {
+            let hand1 = hand1.add_card(alive_cards[j]);
+            let hand2 = hand2.add_card(alive_cards[j]);
+            for k in (j + 1)..(len - 2) {
+                let hand1 = hand1.add_card(alive_cards[k]);
+                let hand2 = hand2.add_card(alive_cards[k]);
+                for m in (k + 1)..(len - 1) {
+                    let hand1 = hand1.add_card(alive_cards[m]);
+                    let hand2 = hand2.add_card(alive_cards[m]);
+                    for n in (m + 1)..len {
+                        let rank1 = hand1.add_card(alive_cards[n]).evaluate();
+                        let rank2 = hand2.add_card(alive_cards[n]).evaluate();
+                        if rank1 > rank2 {
+                            count.0 += 1;
+                        } else if rank1 < rank2 {
+                            count.1 += 1;
+                        } else {
+                            count.2 += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    count
+}
+
+/// Same as `bench_runouts_naive()`, but uses `BoardEvaluator`.
+fn bench_runouts_board_evaluator() -> (u32, u32, u32) {
+    let hand1: Hand = "AsKs".parse().unwrap();
+    let hand2: Hand = "QhQd".parse().unwrap();
+    let evaluator = BoardEvaluator::new(&[hand1, hand2]);
+    let alive_cards = (0..NUMBER_OF_CARDS)
+        .filter(|&c| !(hand1 + hand2).contains(c))
+        .collect::<Vec<_>>();
+    let len = alive_cards.len();
+    let mut count = (0, 0, 0);
+    let mut ranks = [0; 2];
+    for i in 0..(len - 4) {
+        let board = Hand::new().add_card(alive_cards[i]);
+        for j in (i + 1)..(len - 3) {
+            let board = board.add_card(alive_cards[j]);
+            for k in (j + 1)..(len - 2) {
+                let board = board.add_card(alive_cards[k]);
+                for m in (k + 1)..(len - 1) {
+                    let board = board.add_card(alive_cards[m]);
+                    for n in (m + 1)..len {
+                        evaluator.evaluate(&board.add_card(alive_cards[n]), &mut ranks);
+                        if ranks[0] > ranks[1] {
+                            count.0 += 1;
+                        } else if ranks[0] < ranks[1] {
+                            count.1 += 1;
+                        } else {
+                            count.2 += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    count
+}
+
 fn criterion_bench(c: &mut Criterion) {
-    c.bench_function("evaluate_hand (133,784,560 hands)", |b| b.iter(|| bench()));
+    c.bench_function("evaluate_hand (133,784,560 hands)", |b| b.iter(bench));
+
+    let hands = batch_hands();
+    let keys = hands.iter().map(|h| h.get_key()).collect::<Vec<_>>();
+    let masks = hands.iter().map(|h| h.get_mask()).collect::<Vec<_>>();
+    let mut out = vec![0; hands.len()];
+    c.bench_function("evaluate_sequential (2,118,760 hands)", |b| {
+        b.iter(|| {
+            for (hand, out) in hands.iter().zip(out.iter_mut()) {
+                *out = hand.evaluate();
+            }
+        })
+    });
+    c.bench_function("evaluate_batch (2,118,760 hands)", |b| {
+        b.iter(|| evaluate_batch(&hands, &mut out))
+    });
+    c.bench_function("evaluate_batch_soa (2,118,760 hands)", |b| {
+        b.iter(|| evaluate_batch_soa(&keys, &masks, &mut out))
+    });
+    c.bench_function("runouts_naive (1,712,304 boards)", |b| {
+        b.iter(bench_runouts_naive)
+    });
+    c.bench_function("runouts_board_evaluator (1,712,304 boards)", |b| {
+        b.iter(bench_runouts_board_evaluator)
+    });
+    c.bench_function("evaluate_x8 (2,118,760 hands)", |b| {
+        b.iter(|| {
+            for (hands, out) in hands.chunks_exact(8).zip(out.chunks_exact_mut(8)) {
+                let hands: &[Hand; 8] = hands.try_into().unwrap();
+                out.copy_from_slice(&evaluate_x8(hands));
+            }
+        })
+    });
 }
 
 criterion_group!(benches, criterion_bench);
@@ -0,0 +1,54 @@
+// with the `generate-tables` feature, generates the lookup tables from `assets::constants` and
+// checks that the tables committed in `assets` are up to date.
+
+fn main() {
+    #[cfg(feature = "generate-tables")]
+    generate::main();
+}
+
+#[cfg(feature = "generate-tables")]
+mod generate {
+    use scripts::assets::lookup::{LOOKUP, LOOKUP_FLUSH};
+    use scripts::assets::offsets::OFFSETS;
+    use scripts::*;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::Path;
+
+    pub fn main() {
+        println!("cargo:rerun-if-changed=build.rs");
+        println!("cargo:rerun-if-changed=assets/src/constants.rs");
+        println!("cargo:rerun-if-changed=assets/src/lookup.rs");
+        println!("cargo:rerun-if-changed=assets/src/offsets.rs");
+        println!("cargo:rerun-if-changed=scripts/src/lib.rs");
+        println!("cargo:rerun-if-changed=scripts/src/kev");
+
+        // the committed tables are used as is if they are consistent with the constants
+        let committed = generate_lookup_tables(&OFFSETS);
+        let is_up_to_date = match &committed {
+            Some((lookup, lookup_flush)) => {
+                lookup[..] == LOOKUP[..] && lookup_flush[..] == LOOKUP_FLUSH[..]
+            }
+            None => false,
+        };
+
+        let (offsets, lookup, lookup_flush) = match (is_up_to_date, committed) {
+            (true, Some((lookup, lookup_flush))) => (OFFSETS.to_vec(), lookup, lookup_flush),
+            _ => {
+                println!(
+                    "cargo:warning=tables in `assets` do not match `assets::constants`; \
+                     using regenerated tables (run the scripts to update them)"
+                );
+                let (offsets, _) = generate_offsets(&rank_keys());
+                let (lookup, lookup_flush) = generate_lookup_tables(&offsets)
+                    .expect("generated offsets must give a perfect hash function");
+                (offsets, lookup, lookup_flush)
+            }
+        };
+
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        let mut file = File::create(Path::new(&out_dir).join("tables.rs")).unwrap();
+        write!(file, "{}", offsets_source(&offsets)).unwrap();
+        write!(file, "{}", lookup_tables_source(&lookup, &lookup_flush)).unwrap();
+    }
+}
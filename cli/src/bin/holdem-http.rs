@@ -0,0 +1,52 @@
+// HTTP/1.1 server of evaluation and equity queries with an LRU result cache
+// (see `src/http.rs` for the endpoints).
+
+use holdem_hand_evaluator_cli::*;
+use std::process::exit;
+
+const USAGE: &str = "usage: holdem-http [--bind ADDR] [--threads N] [--cache N]
+
+options:
+  --bind ADDR   address to listen on (default: 127.0.0.1:8080)
+  --threads N   number of worker threads (default: number of CPUs)
+  --cache N     maximum number of cached results (default: 100000)";
+
+fn main() {
+    let mut bind = "127.0.0.1:8080".to_string();
+    let mut threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let mut cache = 100_000;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--bind" | "--threads" | "--cache" => args.next(),
+            _ => None,
+        };
+        let parsed = value.and_then(|value| match arg.as_str() {
+            "--bind" => {
+                bind = value;
+                Some(())
+            }
+            "--threads" => value.parse().ok().filter(|&n| n > 0).map(|n| threads = n),
+            _ => value.parse().ok().map(|n| cache = n),
+        });
+        if parsed.is_none() {
+            eprintln!("error: invalid argument '{}'\n\n{}", arg, USAGE);
+            exit(2);
+        }
+    }
+
+    let server = HttpServer::bind(&bind, cache).unwrap_or_else(|e| {
+        eprintln!("error: failed to bind {}: {}", bind, e);
+        exit(1);
+    });
+    eprintln!("listening on http://{}", server.local_addr().unwrap());
+    if let Err(e) = server.run(threads) {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
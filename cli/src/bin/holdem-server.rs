@@ -0,0 +1,20 @@
+// long-running JSON server: reads one request per line from stdin and writes one response per
+// line to stdout (see `src/server.rs` for the protocol).
+
+use holdem_hand_evaluator_cli::*;
+use std::io::{self, BufRead, Write};
+
+fn main() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(stdout, "{}", handle_request(&line))?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+// command-line tool for hand evaluation and equity queries.
+
+use holdem_hand_evaluator_cli::*;
+use std::process::exit;
+
+const USAGE: &str = "usage: holdem <command> [arguments] [--board CARDS] [--dead CARDS] [--json]
+
+commands:
+  eval CARDS                  rank, category and best five cards of 5-9 cards
+  equity HAND1 [HAND2]        heads-up equity (random opponent when HAND2 is omitted)
+  enumerate HAND              hand categories over all runouts to seven cards
+  range-equity RANGE1 RANGE2  heads-up equity of two ranges (e.g., \"QQ+,AKs\" \"22+\")
+
+cards are written like AhKh (ranks 23456789TJQKA, suits cdhs)";
+
+fn run(command: &str, args: &[String], board: &str, dead: &str) -> Result<Json, String> {
+    let arg = |i: usize| args.get(i).map_or("", String::as_str);
+    let expect_args = |min: usize, max: usize| match args.len() {
+        n if n < min || n > max => Err(format!("wrong number of arguments for '{}'", command)),
+        _ => Ok(()),
+    };
+    match command {
+        "eval" => expect_args(1, 1).and_then(|_| eval(arg(0))),
+        "equity" => expect_args(1, 2).and_then(|_| equity(arg(0), arg(1), board, dead)),
+        "enumerate" => expect_args(1, 1).and_then(|_| enumerate(arg(0), board, dead)),
+        "range-equity" => expect_args(2, 2).and_then(|_| range_equity(arg(0), arg(1), board, dead)),
+        _ => Err(format!("unknown command '{}'", command)),
+    }
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut positional = Vec::new();
+    let (mut board, mut dead, mut json) = (String::new(), String::new(), false);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--board" | "--dead" => {
+                let value = args.next().unwrap_or_else(|| {
+                    eprintln!("error: missing value for '{}'\n\n{}", arg, USAGE);
+                    exit(2);
+                });
+                match arg.as_str() {
+                    "--board" => board = value,
+                    _ => dead = value,
+                }
+            }
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => positional.push(arg),
+        }
+    }
+    if positional.is_empty() {
+        eprintln!("{}", USAGE);
+        exit(2);
+    }
+
+    match run(&positional[0], &positional[1..], &board, &dead) {
+        Ok(result) if json => println!("{}", result),
+        Ok(result) => print!("{}", result.to_text()),
+        Err(message) if json => {
+            println!("{}", Json::object(vec![("error", Json::from(message))]));
+            exit(1);
+        }
+        Err(message) => {
+            eprintln!("error: {}", message);
+            exit(1);
+        }
+    }
+}
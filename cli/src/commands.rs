@@ -0,0 +1,297 @@
+use crate::json::*;
+use crate::range::*;
+use holdem_hand_evaluator::*;
+
+const CATEGORY_NAMES: [&str; 9] = [
+    "HighCard",
+    "OnePair",
+    "TwoPair",
+    "ThreeOfAKind",
+    "Straight",
+    "Flush",
+    "FullHouse",
+    "FourOfAKind",
+    "StraightFlush",
+];
+
+/// Returns an error when some of the `hands` share a card.
+fn check_disjoint(hands: &[Hand]) -> Result<(), String> {
+    let total = hands.iter().map(|h| h.len()).sum::<usize>();
+    let union = hands.iter().fold(0, |acc, h| acc | h.get_mask());
+    match union.count_ones() as usize == total {
+        true => Ok(()),
+        false => Err("the same card is used more than once".into()),
+    }
+}
+
+fn check_board(board: &Hand) -> Result<(), String> {
+    match board.len() {
+        0 | 3 | 4 | 5 => Ok(()),
+        n => Err(format!(
+            "board must consist of 0, 3, 4 or 5 cards, but got {}",
+            n
+        )),
+    }
+}
+
+/// Returns the best five cards of `hand` (5-9 cards) that make `hand.evaluate_n()`.
+fn best_five(hand: &Hand) -> Hand {
+    let cards = hand_cards(hand);
+    let rank = hand.evaluate_n();
+    let n = cards.len();
+    // combinations in lexicographic order, so that higher cards are preferred
+    let mut indices = [0, 1, 2, 3, 4];
+    loop {
+        let five = Hand::from_slice(&indices.iter().map(|&i| cards[i]).collect::<Vec<_>>());
+        if five.evaluate() == rank {
+            return five;
+        }
+        let mut i = 4;
+        while indices[i] == n - 5 + i {
+            i -= 1;
+        }
+        indices[i] += 1;
+        for j in (i + 1)..5 {
+            indices[j] = indices[j - 1] + 1;
+        }
+    }
+}
+
+/// Equity of the first hand from win/loss/tie counts.
+fn equity_fields(win1: u64, win2: u64, tie: u64) -> Vec<(&'static str, Json)> {
+    let total = win1 + win2 + tie;
+    let equity = |win: u64| (win as f64 + tie as f64 / 2.0) / total as f64;
+    vec![
+        ("boards", Json::from(total)),
+        ("win1", Json::from(win1)),
+        ("win2", Json::from(win2)),
+        ("tie", Json::from(tie)),
+        ("equity1", Json::from(equity(win1))),
+        ("equity2", Json::from(equity(win2))),
+    ]
+}
+
+/// Evaluates 5-9 `cards`: rank, category and the best five cards.
+pub fn eval(cards: &str) -> Result<Json, String> {
+    let hand = parse_cards(cards)?;
+    if hand.len() < 5 || hand.len() > 9 {
+        return Err(format!("expected 5-9 cards, but got {}", hand.len()));
+    }
+    let rank = hand.evaluate_n();
+    Ok(Json::object(vec![
+        ("cards", Json::from(hand_to_string(&hand))),
+        ("rank", Json::from(rank as u32)),
+        (
+            "category",
+            Json::from(CATEGORY_NAMES[get_hand_category(rank) as usize]),
+        ),
+        ("best_five", Json::from(hand_to_string(&best_five(&hand)))),
+    ]))
+}
+
+/// Computes heads-up equity of `hand1` against `hand2` (an empty `hand2` is a random hand).
+pub fn equity(hand1: &str, hand2: &str, board: &str, dead: &str) -> Result<Json, String> {
+    let (hand1, hand2) = (parse_cards(hand1)?, parse_cards(hand2)?);
+    let (board, dead) = (parse_cards(board)?, parse_cards(dead)?);
+    if hand1.len() != 2 {
+        return Err(format!(
+            "hand1 must consist of 2 cards, but got {}",
+            hand1.len()
+        ));
+    }
+    if !hand2.is_empty() && hand2.len() != 2 {
+        return Err(format!(
+            "hand2 must consist of 2 cards, but got {}",
+            hand2.len()
+        ));
+    }
+    let (win1, win2, tie) =
+        try_heads_up_win_frequency(&hand1, &hand2, &board, &dead).map_err(|e| e.to_string())?;
+    let mut fields = vec![
+        ("hand1", Json::from(hand_to_string(&hand1))),
+        ("hand2", Json::from(hand_to_string(&hand2))),
+        ("board", Json::from(hand_to_string(&board))),
+    ];
+    fields.extend(equity_fields(win1 as u64, win2 as u64, tie as u64));
+    Ok(Json::object(fields))
+}
+
+/// Counts the hand categories of `hand` and `board` over all runouts to seven cards.
+pub fn enumerate(hand: &str, board: &str, dead: &str) -> Result<Json, String> {
+    let (hand, board, dead) = (parse_cards(hand)?, parse_cards(board)?, parse_cards(dead)?);
+    check_disjoint(&[hand, board, dead])?;
+    let cards = hand + board;
+    let counts = try_enumerate_hand_category(&cards, &dead).map_err(|e| e.to_string())?;
+    let categories = CATEGORY_NAMES
+        .iter()
+        .zip(counts.iter())
+        .map(|(name, &count)| (*name, Json::from(count)))
+        .collect();
+    Ok(Json::object(vec![
+        ("cards", Json::from(hand_to_string(&cards))),
+        ("total", Json::from(counts.iter().sum::<u32>())),
+        ("categories", Json::object(categories)),
+    ]))
+}
+
+/// Expands `range` (see `parse_range()`) into two-card hands.
+pub fn range(range: &str) -> Result<Json, String> {
+    let hands = parse_range(range)?;
+    Ok(Json::object(vec![
+        ("count", Json::from(hands.len())),
+        (
+            "hands",
+            Json::Array(
+                hands
+                    .iter()
+                    .map(|h| Json::from(hand_to_string(h)))
+                    .collect(),
+            ),
+        ),
+    ]))
+}
+
+/// Computes heads-up equity of `range1` against `range2` over all non-conflicting matchups.
+/// This enumerates every board of every matchup, so it is slow without a flop.
+pub fn range_equity(range1: &str, range2: &str, board: &str, dead: &str) -> Result<Json, String> {
+    range_equity_with_limit(range1, range2, board, dead, u64::MAX)
+}
+
+/// Same as `range_equity()`, but fails without computing anything when more than `max_boards`
+/// boards would be enumerated in total.
+pub fn range_equity_with_limit(
+    range1: &str,
+    range2: &str,
+    board: &str,
+    dead: &str,
+    max_boards: u64,
+) -> Result<Json, String> {
+    let (range1, range2) = (parse_range(range1)?, parse_range(range2)?);
+    let (board, dead) = (parse_cards(board)?, parse_cards(dead)?);
+    check_board(&board)?;
+    check_disjoint(&[board, dead])?;
+    let used = board.get_mask() | dead.get_mask();
+    let mut matchups = Vec::new();
+    for hand1 in range1.iter().filter(|h| h.get_mask() & used == 0) {
+        for hand2 in range2
+            .iter()
+            .filter(|h| h.get_mask() & (used | hand1.get_mask()) == 0)
+        {
+            matchups.push((hand1, hand2));
+        }
+    }
+    if matchups.is_empty() {
+        return Err("no valid matchup between the ranges".into());
+    }
+
+    let live_cards = 52u64.saturating_sub(4 + board.len() as u64 + dead.len() as u64);
+    let boards_per_matchup = (0..(5 - board.len() as u64))
+        .fold(1, |acc, i| acc * live_cards.saturating_sub(i) / (i + 1));
+    let num_boards = matchups.len() as u64 * boards_per_matchup;
+    if num_boards > max_boards {
+        return Err(format!(
+            "too many boards to enumerate: {} matchups of {} boards (at most {} boards)",
+            matchups.len(),
+            boards_per_matchup,
+            max_boards
+        ));
+    }
+
+    let (mut win1, mut win2, mut tie) = (0, 0, 0);
+    for (hand1, hand2) in &matchups {
+        let result =
+            try_heads_up_win_frequency(hand1, hand2, &board, &dead).map_err(|e| e.to_string())?;
+        win1 += result.0 as u64;
+        win2 += result.1 as u64;
+        tie += result.2 as u64;
+    }
+    let mut fields = vec![
+        ("board", Json::from(hand_to_string(&board))),
+        ("matchups", Json::from(matchups.len() as u32)),
+    ];
+    fields.extend(equity_fields(win1, win2, tie));
+    Ok(Json::object(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let result = eval("AhKhQhJhTh8c6d").unwrap();
+        assert_eq!(result.get("rank"), Some(&Json::from(32777u32)));
+        assert_eq!(result.get("category"), Some(&Json::from("StraightFlush")));
+        assert_eq!(result.get("best_five"), Some(&Json::from("AhKhQhJhTh")));
+        let result = eval("2h2s3d3s4s5d6d").unwrap();
+        assert_eq!(result.get("category"), Some(&Json::from("Straight")));
+        assert_eq!(result.get("best_five"), Some(&Json::from("6d5d4s3s2s")));
+        assert!(eval("AhKh").is_err());
+        assert!(eval("AhAh2c3c4c").is_err());
+    }
+
+    #[test]
+    fn test_equity() {
+        let result = equity("AsAc", "KsKc", "2d3d4d", "").unwrap();
+        let (win1, win2, tie) = heads_up_win_frequency(
+            &parse_cards("AsAc").unwrap(),
+            &parse_cards("KsKc").unwrap(),
+            &parse_cards("2d3d4d").unwrap(),
+            &Hand::new(),
+        );
+        assert_eq!(result.get("win1"), Some(&Json::from(win1)));
+        assert_eq!(result.get("win2"), Some(&Json::from(win2)));
+        assert_eq!(result.get("tie"), Some(&Json::from(tie)));
+        assert!(equity("AsAc", "AsKc", "", "").is_err());
+        assert!(equity("AsAc", "KsKc", "2d3d", "").is_err());
+        assert!(equity("As", "KsKc", "", "").is_err());
+    }
+
+    #[test]
+    fn test_enumerate() {
+        let result = enumerate("AhKh", "QhJhTh", "").unwrap();
+        assert_eq!(result.get("total"), Some(&Json::from(1081u32)));
+        let categories = result.get("categories").unwrap();
+        assert_eq!(categories.get("StraightFlush"), Some(&Json::from(1081u32)));
+        assert!(enumerate("Ah", "", "").is_err());
+        assert!(enumerate("AhKh", "Ah", "").is_err());
+    }
+
+    #[test]
+    fn test_range() {
+        let result = range("AKs").unwrap();
+        assert_eq!(result.get("count"), Some(&Json::from(4usize)));
+        let hands = Json::Array(
+            ["AcKc", "AdKd", "AhKh", "AsKs"]
+                .iter()
+                .map(|&h| Json::from(h))
+                .collect(),
+        );
+        assert_eq!(result.get("hands"), Some(&hands));
+        assert!(range("AKx").is_err());
+    }
+
+    #[test]
+    fn test_range_equity() {
+        let result = range_equity("AA", "KK", "2d3d4c", "").unwrap();
+        assert_eq!(result.get("matchups"), Some(&Json::from(36u32)));
+        let result = range_equity("AsAc", "AA", "", "").unwrap();
+        assert_eq!(result.get("matchups"), Some(&Json::from(1u32)));
+        assert_eq!(result.get("win1"), result.get("win2"));
+        assert!(range_equity("AsAc", "AsAc", "", "").is_err());
+
+        // deuces through queens are dead, so only 4 cards remain for the board
+        let dead = "23456789TJQ"
+            .chars()
+            .flat_map(|r| "cdhs".chars().map(move |s| format!("{}{}", r, s)))
+            .collect::<String>();
+        assert_eq!(
+            range_equity("AA", "KK", "", &dead),
+            Err("not enough live cards".into())
+        );
+
+        // 36 matchups of 990 boards
+        assert!(range_equity_with_limit("AA", "KK", "2d3d4c", "", 35640).is_ok());
+        assert!(range_equity_with_limit("AA", "KK", "2d3d4c", "", 35639).is_err());
+    }
+}
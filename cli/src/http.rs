@@ -0,0 +1,546 @@
+use crate::json::*;
+use crate::range::*;
+use crate::server::*;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// tiny HTTP/1.1 front end of the JSON protocol in `server.rs` (used by `holdem-http`).
+//
+// - `GET /<method>?<param>=<value>&...` (e.g., `/equity?hand1=AhKh&hand2=QsQd&board=2h7h9c`)
+// - `POST /` with a JSON request of `server.rs` as the body
+//
+// responses are JSON with status 200 (result) or 400 (error), and each connection serves a
+// single request. results of `equity` and `enumerate` are cached by the suit-canonical form of
+// their cards, so that e.g. `AhKh` vs. `QsQd` and `AsKs` vs. `QhQd` share an entry, and results
+// of `range_equity` are cached by their parameters as written.
+
+/// Maximum size of the request line and headers in bytes.
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+
+/// Maximum size of the request body in bytes.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Least recently used cache.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+    order: BTreeMap<u64, K>,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> LruCache<K, V> {
+    /// Creates an empty cache that holds at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of `key` and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, tick) = self.entries.get_mut(key)?;
+        let old_tick = std::mem::replace(tick, self.tick);
+        let key = self.order.remove(&old_tick).unwrap();
+        self.order.insert(self.tick, key);
+        Some(value.clone())
+    }
+
+    /// Inserts `value`, evicting the least recently used entry when the cache is full.
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, old_tick)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.order.remove(&old_tick);
+        } else if self.entries.len() > self.capacity {
+            let (_, oldest) = self.order.pop_first().unwrap();
+            self.entries.remove(&oldest);
+        }
+        self.order.insert(self.tick, key);
+    }
+}
+
+/// Fixed-size pool of worker threads.
+pub struct ThreadPool {
+    sender: Option<mpsc::Sender<Box<dyn FnOnce() + Send>>>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    /// Creates a pool of `num_threads` workers.
+    pub fn new(num_threads: usize) -> Self {
+        assert!(num_threads > 0);
+        let (sender, receiver) = mpsc::channel::<Box<dyn FnOnce() + Send>>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..num_threads)
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => return,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Runs `job` on one of the workers.
+    pub fn execute<F: FnOnce() + Send + 'static>(&self, job: F) {
+        self.sender.as_ref().unwrap().send(Box::new(job)).unwrap();
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // closing the channel stops the workers after the queued jobs
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Permutes the suits (16-bit groups) of `mask`.
+fn permute_suits(mask: u64, perm: &[usize; 4]) -> u64 {
+    (0..4).fold(0, |acc, suit| {
+        acc | (((mask >> (16 * suit)) & 0xffff) << (16 * perm[suit]))
+    })
+}
+
+/// Returns the lexicographically smallest `masks` over the 24 suit permutations.
+fn canonical_masks(masks: &[u64; 4]) -> [u64; 4] {
+    let mut best = *masks;
+    for a in 0..4 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                let perm = [a, b, c, 6 - a - b - c];
+                let permuted = [0, 1, 2, 3].map(|i| permute_suits(masks[i], &perm));
+                best = best.min(permuted);
+            }
+        }
+    }
+    best
+}
+
+/// Returns the cache key of a request and the fields of the result that depend on the actual
+/// cards, or `None` when the request is not cached.
+fn cache_key(method: &str, params: &Json) -> Option<(String, Vec<(&'static str, Json)>)> {
+    let cards = |name: &str| match params.get(name) {
+        None => parse_cards(""),
+        Some(value) => parse_cards(value.as_str().unwrap_or("?")),
+    };
+    match method {
+        "equity" => {
+            let (hand1, hand2) = (cards("hand1").ok()?, cards("hand2").ok()?);
+            let (board, dead) = (cards("board").ok()?, cards("dead").ok()?);
+            let masks = [hand1, hand2, board, dead].map(|h| h.get_mask());
+            let key = format!("equity:{:x?}", canonical_masks(&masks));
+            let fields = vec![
+                ("hand1", Json::from(hand_to_string(&hand1))),
+                ("hand2", Json::from(hand_to_string(&hand2))),
+                ("board", Json::from(hand_to_string(&board))),
+            ];
+            Some((key, fields))
+        }
+        "enumerate" => {
+            let (hand, board, dead) = (
+                cards("hand").ok()?,
+                cards("board").ok()?,
+                cards("dead").ok()?,
+            );
+            // the result only depends on the union of `hand` and `board`
+            let masks = [(hand + board).get_mask(), dead.get_mask(), 0, 0];
+            let key = format!("enumerate:{:x?}", canonical_masks(&masks));
+            let fields = vec![("cards", Json::from(hand_to_string(&(hand + board))))];
+            Some((key, fields))
+        }
+        // ranges are not suit-symmetric in general, so they are cached as written
+        "range_equity" => Some((format!("range_equity:{}", params), Vec::new())),
+        _ => None,
+    }
+}
+
+/// Replaces the `fields` of the result in `response`.
+fn patch_result(response: Json, fields: &[(&str, Json)]) -> Json {
+    match response {
+        Json::Object(entries) => Json::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| match (key.as_str(), value) {
+                    ("result", Json::Object(result)) => {
+                        let result = result
+                            .into_iter()
+                            .map(|(k, v)| match fields.iter().find(|(f, _)| *f == k) {
+                                Some((_, new_value)) => (k, new_value.clone()),
+                                None => (k, v),
+                            })
+                            .collect();
+                        (key, Json::Object(result))
+                    }
+                    (_, value) => (key, value),
+                })
+                .collect(),
+        ),
+        response => response,
+    }
+}
+
+/// Decodes `%XX` escapes and `+` in a query component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => result.push(b' '),
+            b'%' if i + 2 < bytes.len()
+                && bytes[i + 1].is_ascii_hexdigit()
+                && bytes[i + 2].is_ascii_hexdigit() =>
+            {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+                result.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 2;
+            }
+            b => result.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+/// Shared state of the server.
+struct State {
+    cache: Mutex<LruCache<String, Json>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// HTTP server of evaluation and equity queries with an LRU result cache.
+pub struct HttpServer {
+    listener: TcpListener,
+    state: Arc<State>,
+}
+
+impl HttpServer {
+    /// Binds the server to `addr` with a cache of `cache_capacity` results.
+    pub fn bind<A: ToSocketAddrs>(addr: A, cache_capacity: usize) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            state: Arc::new(State {
+                cache: Mutex::new(LruCache::new(cache_capacity)),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        })
+    }
+
+    /// Returns the bound address.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves connections with `num_threads` worker threads (never returns).
+    /// Failures to accept a connection (e.g., too many open files) are logged to stderr.
+    pub fn run(self, num_threads: usize) -> io::Result<()> {
+        let pool = ThreadPool::new(num_threads);
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("failed to accept a connection: {}", e);
+                    // avoid a busy loop while the error persists
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+            };
+            let state = Arc::clone(&self.state);
+            pool.execute(move || {
+                let _ = handle_connection(stream, &state);
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reads a request and returns (method, target, body), or an error message.
+fn read_request(stream: &TcpStream) -> Result<(String, String, String), String> {
+    let mut reader = BufReader::new(stream.take((MAX_HEADER_SIZE + MAX_BODY_SIZE) as u64));
+    let mut header_size = 0;
+    let mut read_line = |reader: &mut BufReader<_>| -> Result<String, String> {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        header_size += line.len();
+        match header_size <= MAX_HEADER_SIZE && line.ends_with('\n') {
+            true => Ok(line.trim_end().to_string()),
+            false => Err("malformed or too large header".into()),
+        }
+    };
+
+    let request_line = read_line(&mut reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), target.to_string())
+        }
+        _ => return Err("malformed request line".into()),
+    };
+
+    let mut content_length = 0;
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| "invalid content length")?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err("request body is too large".into());
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+    let body = String::from_utf8(body).map_err(|_| "request body is not UTF-8")?;
+    Ok((method, target, body))
+}
+
+/// Converts an HTTP request to (id, method, params) of the JSON protocol.
+fn to_json_request(method: &str, target: &str, body: &str) -> Result<(Json, String, Json), Json> {
+    let error = |message: &str| {
+        let error = Json::object(vec![
+            ("kind", Json::from("invalid_request")),
+            ("message", Json::from(message)),
+        ]);
+        Json::object(vec![("id", Json::Null), ("error", error)])
+    };
+    match method {
+        "POST" if target == "/" => parse_request(body),
+        "GET" => {
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let params = query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (percent_decode(key), Json::from(percent_decode(value)))
+                })
+                .collect();
+            let method = path.trim_start_matches('/').replace('-', "_");
+            Ok((Json::Null, method, Json::Object(params)))
+        }
+        _ => Err(error("expected GET /<method>?<params> or POST /")),
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, state: &State) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let (response, cache_status) = match read_request(&stream) {
+        Err(message) => {
+            let error = Json::object(vec![
+                ("kind", Json::from("invalid_request")),
+                ("message", Json::from(message)),
+            ]);
+            (
+                Json::object(vec![("id", Json::Null), ("error", error)]),
+                None,
+            )
+        }
+        Ok((method, target, body)) => match to_json_request(&method, &target, &body) {
+            Err(response) => (response, None),
+            Ok((id, method, params)) => handle_cached(id, &method, &params, state),
+        },
+    };
+
+    let status = match response.get("error") {
+        None => "200 OK",
+        Some(_) => "400 Bad Request",
+    };
+    let body = response.to_string();
+    let cache_header = match cache_status {
+        Some(true) => "X-Cache: hit\r\n",
+        Some(false) => "X-Cache: miss\r\n",
+        None => "",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        cache_header,
+        body
+    )?;
+    stream.flush()
+}
+
+/// Handles a request through the cache. Return value: (response, whether the cache was hit)
+fn handle_cached(id: Json, method: &str, params: &Json, state: &State) -> (Json, Option<bool>) {
+    let (key, fields) = match cache_key(method, params) {
+        Some(entry) => entry,
+        None => return (handle_method(id, method, params), None),
+    };
+    let with_id = |response: Json| match response {
+        Json::Object(entries) => Json::Object(
+            entries
+                .into_iter()
+                .map(|(k, v)| match k == "id" {
+                    true => (k, id.clone()),
+                    false => (k, v),
+                })
+                .collect(),
+        ),
+        response => response,
+    };
+
+    // the lock is not held during the computation (the same result may be computed twice)
+    let cached = state.cache.lock().unwrap().get(&key);
+    if let Some(response) = cached {
+        state.hits.fetch_add(1, Ordering::Relaxed);
+        return (with_id(patch_result(response, &fields)), Some(true));
+    }
+    state.misses.fetch_add(1, Ordering::Relaxed);
+    let response = handle_method(Json::Null, method, params);
+    if response.get("result").is_some() {
+        state.cache.lock().unwrap().insert(key, response.clone());
+    }
+    (with_id(response), Some(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "a");
+        cache.insert(2, "b");
+        assert_eq!(cache.get(&1), Some("a"));
+        cache.insert(3, "c");
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.get(&3), Some("c"));
+        cache.insert(3, "d");
+        cache.insert(4, "e");
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&3), Some("d"));
+        assert!(LruCache::<i32, i32>::new(0).is_empty());
+    }
+
+    #[test]
+    fn test_canonical_masks() {
+        let masks = |s: [&str; 4]| s.map(|h| parse_cards(h).unwrap().get_mask());
+        let key1 = canonical_masks(&masks(["AhKh", "QsQd", "2h7h9c", ""]));
+        let key2 = canonical_masks(&masks(["AsKs", "QhQc", "2s7s9d", ""]));
+        let key3 = canonical_masks(&masks(["AhKh", "QsQd", "2h7h9s", ""]));
+        assert_eq!(key1, key2);
+        assert_ne!(key1, key3);
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("QQ%2B%2CAKs"), "QQ+,AKs");
+        assert_eq!(percent_decode("a+b%"), "a b%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    fn request(addr: SocketAddr, request: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    }
+
+    #[test]
+    fn test_server() {
+        let server = HttpServer::bind("127.0.0.1:0", 16).unwrap();
+        let addr = server.local_addr().unwrap();
+        let state = Arc::clone(&server.state);
+        thread::spawn(move || server.run(2));
+
+        let get =
+            |target: &str| request(addr, &format!("GET {} HTTP/1.1\r\nHost: x\r\n\r\n", target));
+        let (head, body) = get("/eval?cards=AhKhQhJhTh8c6d");
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        let result = Json::parse(&body).unwrap();
+        assert_eq!(
+            result.get("result").unwrap().get("rank"),
+            Some(&Json::from(32777u32))
+        );
+
+        let (head, body1) = get("/equity?hand1=AhKh&hand2=QsQd&board=2h7h9c");
+        assert!(head.contains("X-Cache: miss"));
+        let (head, body2) = get("/equity?hand1=AsKs&hand2=QhQc&board=2s7s9d");
+        assert!(head.contains("X-Cache: hit"));
+        let (result1, result2) = (Json::parse(&body1).unwrap(), Json::parse(&body2).unwrap());
+        let (result1, result2) = (
+            result1.get("result").unwrap(),
+            result2.get("result").unwrap(),
+        );
+        assert_eq!(result1.get("win1"), result2.get("win1"));
+        assert_eq!(result2.get("hand1"), Some(&Json::from("AsKs")));
+        assert_eq!(result2.get("board"), Some(&Json::from("9d7s2s")));
+        assert_eq!(state.hits.load(Ordering::Relaxed), 1);
+        assert_eq!(state.misses.load(Ordering::Relaxed), 1);
+
+        let json =
+            r#"{"id": 9, "method": "enumerate", "params": {"hand": "AhKh", "board": "QhJhTh"}}"#;
+        let post = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            json.len(),
+            json
+        );
+        let (head, body) = request(addr, &post);
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        let response = Json::parse(&body).unwrap();
+        assert_eq!(response.get("id"), Some(&Json::from(9u32)));
+        let (_, body) = request(
+            addr,
+            &post.replace("AhKh", "AsKs").replace("QhJhTh", "QsJsTs"),
+        );
+        let response = Json::parse(&body).unwrap();
+        assert_eq!(response.get("id"), Some(&Json::from(9u32)));
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("cards"), Some(&Json::from("AsKsQsJsTs")));
+        assert_eq!(state.hits.load(Ordering::Relaxed), 2);
+
+        let (head, body) = get("/equity?hand1=AhAh");
+        assert!(head.starts_with("HTTP/1.1 400"));
+        assert!(Json::parse(&body).unwrap().get("error").is_some());
+        let (head, _) = get("/unknown");
+        assert!(head.starts_with("HTTP/1.1 400"));
+        let (head, _) = request(addr, "garbage\r\n\r\n");
+        assert!(head.starts_with("HTTP/1.1 400"));
+    }
+}
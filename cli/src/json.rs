@@ -0,0 +1,435 @@
+use std::fmt;
+
+/// Minimal JSON value (object keys keep their insertion order).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Creates an object from `(key, value)` pairs.
+    pub fn object<K: Into<String>>(fields: Vec<(K, Json)>) -> Self {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Returns the value of `key` when `self` is an object that contains it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Parses a JSON text.
+    pub fn parse(s: &str) -> Result<Json, String> {
+        let mut parser = Parser {
+            bytes: s.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        match parser.pos == parser.bytes.len() {
+            true => Ok(value),
+            false => Err(parser.error("unexpected trailing characters")),
+        }
+    }
+
+    /// Returns the string when `self` is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Formats `self` as indented `key: value` lines for human-readable output.
+    pub fn to_text(&self) -> String {
+        let mut result = String::new();
+        write_text(self, 0, &mut result);
+        result
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.into())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(x: u32) -> Self {
+        Json::Number(x as f64)
+    }
+}
+
+impl From<u64> for Json {
+    fn from(x: u64) -> Self {
+        Json::Number(x as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(x: usize) -> Self {
+        Json::Number(x as f64)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(x: f64) -> Self {
+        Json::Number(x)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+/// Writes `s` as a JSON string literal.
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => write!(f, "{}", ch)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Compact JSON encoding.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(x) if !x.is_finite() => f.write_str("null"),
+            Json::Number(x) if x.fract() == 0.0 && x.abs() < 1e15 => write!(f, "{}", *x as i64),
+            Json::Number(x) => write!(f, "{}", x),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                f.write_str("[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+/// Maximum nesting depth of arrays and objects accepted by `Json::parse()`.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at position {}", message, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        match self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            true => {
+                self.pos += literal.len();
+                Ok(())
+            }
+            false => Err(self.error("invalid literal")),
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Json, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            None => Err(self.error("unexpected end of input")),
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.parse_string().map(Json::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.bytes.get(self.pos) == Some(&b']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.parse_value(depth + 1)?);
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(values));
+                        }
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.bytes.get(self.pos) == Some(&b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b'"') {
+                        return Err(self.error("expected string key"));
+                    }
+                    let key = self.parse_string()?;
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b':') {
+                        return Err(self.error("expected ':'"));
+                    }
+                    self.pos += 1;
+                    fields.push((key, self.parse_value(depth + 1)?));
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.bytes.get(self.pos) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap();
+        let is_valid = !text.starts_with("-.") && !text.starts_with('.') && !text.ends_with('.');
+        match text.parse::<f64>() {
+            Ok(x) if is_valid => Ok(Json::Number(x)),
+            _ => {
+                self.pos = start;
+                Err(self.error("invalid number"))
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, String> {
+        let hex = self.bytes.get(self.pos..self.pos + 4);
+        let value = hex
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u32::from_str_radix(h, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(value)
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        // the opening quote
+        self.pos += 1;
+        let mut result = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(&b) = self.bytes.get(self.pos) {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // the input is a `str`, and runs of unescaped bytes end at ASCII characters
+            result.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap());
+            match self.bytes.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(result);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = self.bytes.get(self.pos).copied();
+                    self.pos += 1;
+                    match escaped {
+                        Some(b'"') => result.push('"'),
+                        Some(b'\\') => result.push('\\'),
+                        Some(b'/') => result.push('/'),
+                        Some(b'b') => result.push('\u{8}'),
+                        Some(b'f') => result.push('\u{c}'),
+                        Some(b'n') => result.push('\n'),
+                        Some(b'r') => result.push('\r'),
+                        Some(b't') => result.push('\t'),
+                        Some(b'u') => {
+                            let mut code = self.parse_hex4()?;
+                            if (0xd800..0xdc00).contains(&code)
+                                && self.bytes[self.pos..].starts_with(b"\\u")
+                            {
+                                self.pos += 2;
+                                let low = self.parse_hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(self.error("invalid surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            let ch = char::from_u32(code)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            result.push(ch);
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    }
+                }
+                Some(_) => return Err(self.error("control character in string")),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+}
+
+fn write_text(value: &Json, indent: usize, out: &mut String) {
+    match value {
+        Json::Object(fields) => {
+            for (key, value) in fields {
+                out.push_str(&" ".repeat(indent));
+                out.push_str(key);
+                out.push(':');
+                match value {
+                    Json::Object(_) => {
+                        out.push('\n');
+                        write_text(value, indent + 2, out);
+                    }
+                    _ => {
+                        out.push(' ');
+                        write_text(value, indent, out);
+                        out.push('\n');
+                    }
+                }
+            }
+        }
+        Json::Array(values) => {
+            let items = values.iter().map(|v| v.to_text()).collect::<Vec<_>>();
+            out.push_str(&items.join(" "));
+        }
+        Json::String(s) => out.push_str(s),
+        Json::Number(x) if x.fract() != 0.0 => out.push_str(&format!("{:.4}", x)),
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let value = Json::object(vec![
+            ("name", Json::from("a\"b\\c\n")),
+            ("count", Json::from(3u32)),
+            ("ratio", Json::from(0.25)),
+            ("list", Json::Array(vec![Json::Null, Json::Bool(true)])),
+            ("empty", Json::Object(Vec::new())),
+        ]);
+        assert_eq!(
+            value.to_string(),
+            r#"{"name":"a\"b\\c\n","count":3,"ratio":0.25,"list":[null,true],"empty":{}}"#
+        );
+        assert_eq!(value.get("count"), Some(&Json::Number(3.0)));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = r#" {"id": 7, "list": [1.5, -2e3, true, false, null], "s": "a\"\n\u00e9\ud83d\ude00", "o": {}} "#;
+        let value = Json::parse(text).unwrap();
+        assert_eq!(value.get("id"), Some(&Json::Number(7.0)));
+        assert_eq!(
+            value.get("list"),
+            Some(&Json::Array(vec![
+                Json::Number(1.5),
+                Json::Number(-2000.0),
+                Json::Bool(true),
+                Json::Bool(false),
+                Json::Null
+            ]))
+        );
+        assert_eq!(
+            value.get("s").and_then(Json::as_str),
+            Some("a\"\n\u{e9}\u{1f600}")
+        );
+        assert_eq!(value.get("o"), Some(&Json::Object(Vec::new())));
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+
+        for invalid in &[
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "\"abc",
+            "tru",
+            "1 2",
+            ".5",
+            "\"\\x\"",
+            "{1: 2}",
+        ] {
+            assert!(Json::parse(invalid).is_err(), "{}", invalid);
+        }
+        assert!(Json::parse(&"[".repeat(1000)).is_err());
+    }
+
+    #[test]
+    fn test_text() {
+        let value = Json::object(vec![
+            ("rank", Json::from(32777u32)),
+            ("equity", Json::from(0.5)),
+            ("nested", Json::object(vec![("cards", Json::from("AhKh"))])),
+        ]);
+        assert_eq!(
+            value.to_text(),
+            "rank: 32777\nequity: 0.5000\nnested:\n  cards: AhKh\n"
+        );
+    }
+}
@@ -0,0 +1,10 @@
+mod commands;
+mod http;
+mod json;
+mod range;
+mod server;
+pub use commands::*;
+pub use http::*;
+pub use json::*;
+pub use range::*;
+pub use server::*;
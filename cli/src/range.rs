@@ -0,0 +1,155 @@
+use holdem_hand_evaluator::Hand;
+use std::collections::HashSet;
+
+const RANK_CHARS: &[u8; 13] = b"23456789TJQKA";
+const SUIT_CHARS: &[u8; 4] = b"cdhs";
+
+/// Parses cards such as `"AhKh"`; unlike `str::parse::<Hand>()`, duplicated cards are errors.
+pub fn parse_cards(s: &str) -> Result<Hand, String> {
+    let hand = s.parse::<Hand>()?;
+    if hand.len() * 2 != s.len() {
+        return Err(format!("duplicated card in '{}'", s));
+    }
+    Ok(hand)
+}
+
+/// Returns the card IDs in `hand` in descending order.
+pub fn hand_cards(hand: &Hand) -> Vec<usize> {
+    (0..52).rev().filter(|&card| hand.contains(card)).collect()
+}
+
+/// Returns the string representation of `card` (e.g., `"Ah"` for 50).
+pub fn card_to_string(card: usize) -> String {
+    let rank = RANK_CHARS[card / 4] as char;
+    let suit = SUIT_CHARS[card % 4] as char;
+    format!("{}{}", rank, suit)
+}
+
+/// Returns the cards in `hand` from the highest (e.g., `"AhKh"`).
+pub fn hand_to_string(hand: &Hand) -> String {
+    hand_cards(hand).into_iter().map(card_to_string).collect()
+}
+
+fn parse_rank(ch: u8) -> Option<usize> {
+    RANK_CHARS
+        .iter()
+        .position(|&c| c == ch.to_ascii_uppercase())
+}
+
+/// Two-card holdings of ranks `r1` and `r2`; `suited`: `Some(true)` for suited only,
+/// `Some(false)` for offsuit only, and `None` for both.
+fn combos(r1: usize, r2: usize, suited: Option<bool>, result: &mut Vec<Hand>) {
+    for s1 in 0..4 {
+        for s2 in 0..4 {
+            if (r1 == r2 && s1 >= s2) || suited.is_some_and(|suited| suited != (s1 == s2)) {
+                continue;
+            }
+            result.push(Hand::new().add_card(r1 * 4 + s1).add_card(r2 * 4 + s2));
+        }
+    }
+}
+
+/// Parses a single holding class: `"QQ"`, `"AKs"`, `"AKo"` or `"AK"`.
+/// Return value: (higher rank, lower rank, suitedness)
+fn parse_class(s: &str) -> Result<(usize, usize, Option<bool>), String> {
+    let bytes = s.as_bytes();
+    let error = || format!("invalid range element '{}'", s);
+    if bytes.len() < 2 || bytes.len() > 3 {
+        return Err(error());
+    }
+    let r1 = parse_rank(bytes[0]).ok_or_else(error)?;
+    let r2 = parse_rank(bytes[1]).ok_or_else(error)?;
+    let suited = match bytes.get(2).map(|ch| ch.to_ascii_lowercase()) {
+        None => None,
+        Some(b's') if r1 != r2 => Some(true),
+        Some(b'o') if r1 != r2 => Some(false),
+        _ => return Err(error()),
+    };
+    Ok((r1.max(r2), r1.min(r2), suited))
+}
+
+/// Parses a comma-separated range such as `"QQ+, AKs, A5s-A2s, KQo, AhKh"` into two-card
+/// hands. `+` extends pairs up to aces and kickers up to one rank below the higher card, and
+/// `-` spans classes with the same higher card (or pairs). Duplicated hands are removed.
+pub fn parse_range(s: &str) -> Result<Vec<Hand>, String> {
+    let mut result = Vec::new();
+    for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let bytes = token.as_bytes();
+        let is_combo = bytes.len() == 4
+            && SUIT_CHARS.contains(&bytes[1].to_ascii_lowercase())
+            && SUIT_CHARS.contains(&bytes[3].to_ascii_lowercase());
+        if is_combo {
+            result.push(parse_cards(token)?);
+            continue;
+        }
+
+        let error = || format!("invalid range element '{}'", token);
+        let (first, last) = if let Some(class) = token.strip_suffix('+') {
+            let (high, low, suited) = parse_class(class)?;
+            match high == low {
+                true => ((high, low, suited), (12, 12, suited)),
+                false => ((high, low, suited), (high, high - 1, suited)),
+            }
+        } else if let Some((from, to)) = token.split_once('-') {
+            let from = parse_class(from.trim())?;
+            let to = parse_class(to.trim())?;
+            let is_pairs = from.0 == from.1 && to.0 == to.1;
+            if !is_pairs && (from.0 != to.0 || from.0 == from.1 || to.0 == to.1 || from.2 != to.2) {
+                return Err(error());
+            }
+            (from, to)
+        } else {
+            let class = parse_class(token)?;
+            (class, class)
+        };
+
+        let (low_min, low_max) = (first.1.min(last.1), first.1.max(last.1));
+        for low in low_min..=low_max {
+            let high = if first.0 == first.1 { low } else { first.0 };
+            combos(high, low, first.2, &mut result);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    result.retain(|hand| seen.insert(hand.get_mask()));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cards() {
+        let hand = parse_cards("AhKh2c").unwrap();
+        assert_eq!(hand_cards(&hand), vec![50, 46, 0]);
+        assert_eq!(hand_to_string(&hand), "AhKh2c");
+        assert!(parse_cards("AhAh").is_err());
+        assert!(parse_cards("Ax").is_err());
+    }
+
+    #[test]
+    fn test_range() {
+        let count = |s: &str| parse_range(s).unwrap().len();
+        assert_eq!(count("AA"), 6);
+        assert_eq!(count("AKs"), 4);
+        assert_eq!(count("AKo"), 12);
+        assert_eq!(count("AK"), 16);
+        assert_eq!(count("QQ+"), 18);
+        assert_eq!(count("22+"), 78);
+        assert_eq!(count("ATs+"), 16);
+        assert_eq!(count("KTo+"), 36);
+        assert_eq!(count("A5s-A2s"), 16);
+        assert_eq!(count("55-22"), 24);
+        assert_eq!(count("AhKh, AKs, QQ"), 10);
+        assert_eq!(count(""), 0);
+        assert_eq!(
+            parse_range("AhKh").unwrap(),
+            vec![parse_cards("AhKh").unwrap()]
+        );
+        assert!(parse_range("AKx").is_err());
+        assert!(parse_range("AAs").is_err());
+        assert!(parse_range("AKs-QJs").is_err());
+        assert!(parse_range("AhAh").is_err());
+    }
+}
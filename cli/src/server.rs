@@ -0,0 +1,235 @@
+use crate::commands::*;
+use crate::json::*;
+use std::cell::Cell;
+use std::panic;
+use std::sync::Once;
+
+// line-oriented JSON protocol used by `holdem-server`.
+//
+// request:  {"id": 1, "method": "equity", "params": {"hand1": "AhKh", "hand2": "QsQd"}}
+// response: {"id": 1, "result": {...}} or {"id": 1, "error": {"kind": "...", "message": "..."}}
+//
+// methods and parameters (parameters in brackets are optional and default to no cards):
+// - eval: cards
+// - equity: hand1, [hand2], [board], [dead]
+// - enumerate: hand, [board], [dead]
+// - range: range
+// - range_equity: range1, range2, [board], [dead]
+//   (rejected when more than `MAX_RANGE_EQUITY_BOARDS` boards would be enumerated)
+
+/// Maximum number of boards enumerated by a `range_equity` request (under a second of work in
+/// release builds; e.g., 58 preflop matchups or two 300-combo ranges on the flop).
+pub const MAX_RANGE_EQUITY_BOARDS: u64 = 100_000_000;
+
+fn error(id: Json, kind: &str, message: String) -> Json {
+    let error = Json::object(vec![
+        ("kind", Json::from(kind)),
+        ("message", Json::from(message)),
+    ]);
+    Json::object(vec![("id", id), ("error", error)])
+}
+
+/// Returns the string parameter `name`; missing optional parameters are empty strings.
+fn param<'a>(params: &'a Json, name: &str, required: bool) -> Result<&'a str, String> {
+    match params.get(name) {
+        Some(value) => value
+            .as_str()
+            .ok_or_else(|| format!("parameter '{}' must be a string", name)),
+        None if required => Err(format!("missing parameter '{}'", name)),
+        None => Ok(""),
+    }
+}
+
+fn dispatch(method: &str, params: &Json) -> Result<Result<Json, String>, String> {
+    let p = |name: &str| param(params, name, true);
+    let opt = |name: &str| param(params, name, false);
+    Ok(match method {
+        "eval" => eval(p("cards")?),
+        "equity" => equity(p("hand1")?, opt("hand2")?, opt("board")?, opt("dead")?),
+        "enumerate" => enumerate(p("hand")?, opt("board")?, opt("dead")?),
+        "range" => range(p("range")?),
+        "range_equity" => range_equity_with_limit(
+            p("range1")?,
+            p("range2")?,
+            opt("board")?,
+            opt("dead")?,
+            MAX_RANGE_EQUITY_BOARDS,
+        ),
+        _ => return Err(format!("unknown method '{}'", method)),
+    })
+}
+
+/// Parses a request line into (id, method, params), or returns the error response.
+pub fn parse_request(line: &str) -> Result<(Json, String, Json), Json> {
+    let request = Json::parse(line).map_err(|message| error(Json::Null, "parse_error", message))?;
+    let id = request.get("id").cloned().unwrap_or(Json::Null);
+    let method = match request.get("method").and_then(Json::as_str) {
+        Some(method) => method.to_string(),
+        None => return Err(error(id, "invalid_request", "missing method".into())),
+    };
+    let params = match request.get("params") {
+        Some(params @ Json::Object(_)) => params.clone(),
+        Some(_) => {
+            return Err(error(
+                id,
+                "invalid_request",
+                "params must be an object".into(),
+            ))
+        }
+        None => Json::Object(Vec::new()),
+    };
+    Ok((id, method, params))
+}
+
+thread_local! {
+    // whether the current thread is in `catch_panic()`
+    static IN_HANDLER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs (once) a panic hook that is silent for the panics caught by `catch_panic()` and
+/// calls the previous hook for the others.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_HANDLER.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Calls `f`, returning the panic message instead of printing it if `f` panics.
+fn catch_panic<T>(f: impl FnOnce() -> T + panic::UnwindSafe) -> Result<T, String> {
+    install_panic_hook();
+    IN_HANDLER.with(|flag| flag.set(true));
+    let result = panic::catch_unwind(f);
+    IN_HANDLER.with(|flag| flag.set(false));
+    result.map_err(|payload| match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".into(),
+        },
+    })
+}
+
+/// Calls `method` with `params` and returns the response.
+pub fn handle_method(id: Json, method: &str, params: &Json) -> Json {
+    // inputs are validated by the `try_` functions of the evaluator; catching panics is only a
+    // last resort that keeps the server alive
+    match catch_panic(|| dispatch(method, params)) {
+        Ok(Ok(Ok(result))) => Json::object(vec![("id", id), ("result", result)]),
+        Ok(Ok(Err(message))) => error(id, "invalid_input", message),
+        Ok(Err(message)) => error(id, "invalid_request", message),
+        Err(message) => error(
+            id,
+            "internal_error",
+            format!("unexpected panic: {}", message),
+        ),
+    }
+}
+
+/// Handles one request line and returns the response.
+/// Errors are reported in the response with one of the following kinds: `parse_error`
+/// (malformed JSON), `invalid_request` (unknown method or missing parameters),
+/// `invalid_input` (invalid cards or ranges) and `internal_error`.
+pub fn handle_request(line: &str) -> Json {
+    match parse_request(line) {
+        Ok((id, method, params)) => handle_method(id, &method, &params),
+        Err(response) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_kind(response: &Json) -> Option<&str> {
+        response.get("error")?.get("kind")?.as_str()
+    }
+
+    #[test]
+    fn test_requests() {
+        let response = handle_request(
+            r#"{"id": "a", "method": "eval", "params": {"cards": "AhKhQhJhTh8c6d"}}"#,
+        );
+        assert_eq!(response.get("id"), Some(&Json::from("a")));
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("rank"), Some(&Json::from(32777u32)));
+
+        let response = handle_request(
+            r#"{"id": 2, "method": "equity", "params": {"hand1": "AsAc", "hand2": "KsKc", "board": "2d3d4d"}}"#,
+        );
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("boards"), Some(&Json::from(990u32)));
+
+        let response = handle_request(
+            r#"{"id": 3, "method": "enumerate", "params": {"hand": "AhKh", "board": "QhJhTh"}}"#,
+        );
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("total"), Some(&Json::from(1081u32)));
+
+        let response =
+            handle_request(r#"{"id": 4, "method": "range", "params": {"range": "QQ+"}}"#);
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("count"), Some(&Json::from(18usize)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let response = handle_request("{");
+        assert_eq!(response.get("id"), Some(&Json::Null));
+        assert_eq!(error_kind(&response), Some("parse_error"));
+
+        let response = handle_request(r#"{"id": 1}"#);
+        assert_eq!(error_kind(&response), Some("invalid_request"));
+        let response = handle_request(r#"{"id": 1, "method": "foo"}"#);
+        assert_eq!(error_kind(&response), Some("invalid_request"));
+        let response = handle_request(r#"{"id": 1, "method": "eval"}"#);
+        assert_eq!(error_kind(&response), Some("invalid_request"));
+        let response = handle_request(r#"{"id": 1, "method": "eval", "params": {"cards": 1}}"#);
+        assert_eq!(error_kind(&response), Some("invalid_request"));
+
+        // these are rejected by `try_heads_up_win_frequency()`
+        let response = handle_request(
+            r#"{"id": 5, "method": "equity", "params": {"hand1": "AsAc", "hand2": "AsKc"}}"#,
+        );
+        assert_eq!(response.get("id"), Some(&Json::from(5u32)));
+        assert_eq!(error_kind(&response), Some("invalid_input"));
+        let response = handle_request(
+            r#"{"id": 6, "method": "equity", "params": {"hand1": "AsAc", "board": "2c3c"}}"#,
+        );
+        assert_eq!(error_kind(&response), Some("invalid_input"));
+
+        // deuces through queens are dead, so the board cannot be completed
+        let dead = "23456789TJQ"
+            .chars()
+            .flat_map(|r| "cdhs".chars().map(move |s| format!("{}{}", r, s)))
+            .collect::<String>();
+        let response = handle_request(&format!(
+            r#"{{"id": 7, "method": "range_equity", "params": {{"range1": "AA", "range2": "KK", "dead": "{}"}}}}"#,
+            dead
+        ));
+        assert_eq!(error_kind(&response), Some("invalid_input"));
+
+        // 1,712,304 boards for each of the 72 matchups
+        let response = handle_request(
+            r#"{"id": 8, "method": "range_equity", "params": {"range1": "AA", "range2": "KK,QQ"}}"#,
+        );
+        assert_eq!(error_kind(&response), Some("invalid_input"));
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(|| 1), Ok(1));
+        assert_eq!(catch_panic(|| -> () { panic!("boom") }), Err("boom".into()));
+        let n = 2;
+        assert_eq!(
+            catch_panic(|| -> () { panic!("{} cards", n) }),
+            Err("2 cards".into())
+        );
+        assert!(!IN_HANDLER.with(Cell::get));
+    }
+}
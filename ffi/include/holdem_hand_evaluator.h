@@ -0,0 +1,72 @@
+/* C interface of holdem-hand-evaluator (see ffi/src/lib.rs) */
+
+#ifndef HOLDEM_HAND_EVALUATOR_H
+#define HOLDEM_HAND_EVALUATOR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* card ID: 0-3 => 2c2d2h2s, 4-7 => 3c3d3h3s, ..., 48-51 => AcAdAhAs */
+
+/* set of cards; treat the fields as opaque */
+typedef struct {
+    uint64_t key;
+    uint64_t mask;
+} hhe_hand;
+
+typedef struct {
+    uint32_t win1;
+    uint32_t win2;
+    uint32_t tie;
+} hhe_win_frequency;
+
+/* hand categories returned by hhe_hand_category() */
+enum {
+    HHE_HIGH_CARD = 0,
+    HHE_ONE_PAIR = 1,
+    HHE_TWO_PAIR = 2,
+    HHE_THREE_OF_A_KIND = 3,
+    HHE_STRAIGHT = 4,
+    HHE_FLUSH = 5,
+    HHE_FULL_HOUSE = 6,
+    HHE_FOUR_OF_A_KIND = 7,
+    HHE_STRAIGHT_FLUSH = 8
+};
+
+/* returns an empty hand */
+hhe_hand hhe_hand_new(void);
+
+/* creates a hand from `len` card IDs; returns 0 on success, -1 on invalid or duplicated cards */
+int32_t hhe_hand_from_cards(const uint8_t *cards, size_t len, hhe_hand *out);
+
+/* returns `hand` with `card` added (unchanged when `card` is invalid or already included) */
+hhe_hand hhe_hand_add_card(hhe_hand hand, uint32_t card);
+
+/* returns the number of cards in `hand` */
+uint32_t hhe_hand_len(hhe_hand hand);
+
+/* returns the hand strength of a 5-9 card hand (stronger hand yields higher value), or -1
+ * (also for a hand whose fields were not set by these functions) */
+int32_t hhe_evaluate(hhe_hand hand);
+
+/* returns the hand category of a hand strength, or -1 */
+int32_t hhe_hand_category(uint16_t hand_rank);
+
+/* computes heads-up win frequency; returns 0 on success, -1 on invalid arguments
+ * (`hand1`: 2 cards, `hand2`: 0-2 cards, `board`: 0, 3, 4 or 5 cards, no shared cards) */
+int32_t hhe_heads_up_win_frequency(hhe_hand hand1, hhe_hand hand2, hhe_hand board,
+                                   hhe_hand dead_cards, hhe_win_frequency *out);
+
+/* writes the counts of the 9 hand categories reachable from `hand` (2-7 cards) to `out`;
+ * returns 0 on success, -1 on invalid arguments */
+int32_t hhe_enumerate_hand_category(hhe_hand hand, hhe_hand dead_cards, uint32_t *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* HOLDEM_HAND_EVALUATOR_H */
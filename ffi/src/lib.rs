@@ -0,0 +1,238 @@
+// C ABI of the evaluator, built as shared and static libraries.
+// the declarations are in `include/holdem_hand_evaluator.h`.
+// the functions never panic: invalid arguments are reported by a negative return value.
+
+use holdem_hand_evaluator::*;
+
+const NUMBER_OF_CARDS: usize = 52;
+
+/// Returns `hand` if its key is consistent with its mask, i.e., it was built by these
+/// functions; a forged or zeroed `hhe_hand` could cause out-of-bounds table reads.
+fn checked(hand: Hand) -> Option<Hand> {
+    let mask = hand.get_mask();
+    if mask & !0x1fff_1fff_1fff_1fff != 0 {
+        return None;
+    }
+    let mut rebuilt = Hand::new();
+    let mut bits = mask;
+    while bits != 0 {
+        let bit = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+        rebuilt = rebuilt.add_card((bit % 16) * 4 + bit / 16);
+    }
+    Some(hand).filter(|hand| *hand == rebuilt)
+}
+
+/// Win frequency returned by `hhe_heads_up_win_frequency()`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HheWinFrequency {
+    pub win1: u32,
+    pub win2: u32,
+    pub tie: u32,
+}
+
+/// Returns an empty hand.
+#[no_mangle]
+pub extern "C" fn hhe_hand_new() -> Hand {
+    Hand::new()
+}
+
+/// Creates a hand from `len` card IDs in the range \[0, 51\] and writes it to `out`.
+/// Returns 0 on success, or -1 when a card is out of range or duplicated.
+///
+/// # Safety
+/// `cards` must point to `len` readable elements (or `len` must be 0), and `out` must be a
+/// valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn hhe_hand_from_cards(cards: *const u8, len: usize, out: *mut Hand) -> i32 {
+    if out.is_null() || (cards.is_null() && len > 0) {
+        return -1;
+    }
+    let mut hand = Hand::new();
+    for i in 0..len {
+        let card = *cards.add(i) as usize;
+        if card >= NUMBER_OF_CARDS || hand.contains(card) {
+            return -1;
+        }
+        hand = hand.add_card(card);
+    }
+    *out = hand;
+    0
+}
+
+/// Adds `card` to `hand` and returns the new hand; `hand` is returned unchanged when `card`
+/// is out of range or already included, or when `hand` is invalid.
+#[no_mangle]
+pub extern "C" fn hhe_hand_add_card(hand: Hand, card: u32) -> Hand {
+    let card = card as usize;
+    match checked(hand) {
+        Some(valid) if card < NUMBER_OF_CARDS && !valid.contains(card) => valid.add_card(card),
+        _ => hand,
+    }
+}
+
+/// Returns the number of cards in `hand`.
+#[no_mangle]
+pub extern "C" fn hhe_hand_len(hand: Hand) -> u32 {
+    hand.len() as u32
+}
+
+/// Returns the hand strength of `hand` (5-9 cards; see `Hand::evaluate_n()`), or -1 when the
+/// number of cards is out of range or `hand` is invalid.
+#[no_mangle]
+pub extern "C" fn hhe_evaluate(hand: Hand) -> i32 {
+    match checked(hand) {
+        Some(hand) if (5..=9).contains(&hand.len()) => hand.evaluate_n() as i32,
+        _ => -1,
+    }
+}
+
+/// Returns the hand category (0: high card, ..., 8: straight flush) of a hand strength, or -1
+/// when `hand_rank` is not a valid hand strength.
+#[no_mangle]
+pub extern "C" fn hhe_hand_category(hand_rank: u16) -> i32 {
+    match hand_rank >> 12 {
+        0..=8 => get_hand_category(hand_rank) as i32,
+        _ => -1,
+    }
+}
+
+/// Computes heads-up win frequency (see `heads_up_win_frequency()`) and writes it to `out`.
+/// `hand1` must consist of 2 cards, `hand2` of at most 2 cards, `board` of 0, 3, 4 or 5 cards,
+/// and the four hands must be valid and must not share any card. Returns 0 on success, or -1
+/// otherwise.
+///
+/// # Safety
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn hhe_heads_up_win_frequency(
+    hand1: Hand,
+    hand2: Hand,
+    board: Hand,
+    dead_cards: Hand,
+    out: *mut HheWinFrequency,
+) -> i32 {
+    let hands = [hand1, hand2, board, dead_cards];
+    if out.is_null() || hands.iter().any(|&hand| checked(hand).is_none()) {
+        return -1;
+    }
+    match try_heads_up_win_frequency(&hand1, &hand2, &board, &dead_cards) {
+        Ok((win1, win2, tie)) => {
+            *out = HheWinFrequency { win1, win2, tie };
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
+/// Enumerates possible hand categories from `hand` (see `enumerate_hand_category()`) and
+/// writes the counts of the 9 categories to `out`. `hand` must consist of 2-7 cards, and the
+/// two hands must be valid and must not share any card. Returns 0 on success, or -1 otherwise.
+///
+/// # Safety
+/// `out` must point to 9 writable elements.
+#[no_mangle]
+pub unsafe extern "C" fn hhe_enumerate_hand_category(
+    hand: Hand,
+    dead_cards: Hand,
+    out: *mut u32,
+) -> i32 {
+    if out.is_null() || checked(hand).is_none() || checked(dead_cards).is_none() {
+        return -1;
+    }
+    match try_enumerate_hand_category(&hand, &dead_cards) {
+        Ok(result) => {
+            core::ptr::copy_nonoverlapping(result.as_ptr(), out, result.len());
+            0
+        }
+        Err(_) => -1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi() {
+        let mut hand = Hand::new();
+        let cards = [48, 44, 40, 36, 32, 3, 7];
+        assert_eq!(
+            unsafe { hhe_hand_from_cards(cards.as_ptr(), 7, &mut hand) },
+            0
+        );
+        assert_eq!(hand, Hand::from_slice(&[48, 44, 40, 36, 32, 3, 7]));
+        assert_eq!(hhe_evaluate(hand), hand.evaluate() as i32);
+        assert_eq!(hhe_hand_category(hand.evaluate()), 8);
+        assert_eq!(hhe_evaluate(hhe_hand_new().add_card(0)), -1);
+        assert_eq!(hhe_hand_category(10 << 12), -1);
+        assert_eq!(hhe_hand_len(hhe_hand_add_card(hand, 3)), 7);
+        assert_eq!(hhe_hand_len(hhe_hand_add_card(hand, 52)), 7);
+
+        let invalid = [0, 52];
+        let duplicated = [0, 0];
+        assert_eq!(
+            unsafe { hhe_hand_from_cards(invalid.as_ptr(), 2, &mut hand) },
+            -1
+        );
+        assert_eq!(
+            unsafe { hhe_hand_from_cards(duplicated.as_ptr(), 2, &mut hand) },
+            -1
+        );
+
+        let hand1 = Hand::from_slice(&[51, 47]);
+        let hand2 = Hand::from_slice(&[46, 42]);
+        let board = Hand::from_slice(&[0, 5, 10]);
+        let mut freq = HheWinFrequency::default();
+        let result =
+            unsafe { hhe_heads_up_win_frequency(hand1, hand2, board, Hand::new(), &mut freq) };
+        let expected = heads_up_win_frequency(&hand1, &hand2, &board, &Hand::new());
+        assert_eq!(result, 0);
+        assert_eq!((freq.win1, freq.win2, freq.tie), expected);
+        let result =
+            unsafe { hhe_heads_up_win_frequency(hand1, hand1, board, Hand::new(), &mut freq) };
+        assert_eq!(result, -1);
+
+        let mut counts = [0; 9];
+        let result =
+            unsafe { hhe_enumerate_hand_category(hand1 + board, Hand::new(), counts.as_mut_ptr()) };
+        assert_eq!(result, 0);
+        assert_eq!(
+            counts,
+            enumerate_hand_category(&(hand1 + board), &Hand::new())
+        );
+        let result = unsafe { hhe_enumerate_hand_category(hand1, hand1, counts.as_mut_ptr()) };
+        assert_eq!(result, -1);
+    }
+
+    #[test]
+    fn test_invalid_hands() {
+        let hand = Hand::from_slice(&[48, 44, 40, 36, 32, 3, 7]);
+        let zeroed = hhe_hand_from_raw(0, 0);
+        let forged = hhe_hand_from_raw(hand.get_key() + 1, hand.get_mask());
+        let out_of_range = hhe_hand_from_raw(hand.get_key(), hand.get_mask() | 1 << 13);
+        for &invalid in &[zeroed, forged, out_of_range] {
+            assert_eq!(hhe_evaluate(invalid), -1);
+            assert_eq!(hhe_hand_add_card(invalid, 0), invalid);
+            let mut freq = HheWinFrequency::default();
+            let empty = Hand::new();
+            let result =
+                unsafe { hhe_heads_up_win_frequency(hand, empty, empty, invalid, &mut freq) };
+            assert_eq!(result, -1);
+            let mut counts = [0; 9];
+            let result =
+                unsafe { hhe_enumerate_hand_category(invalid, empty, counts.as_mut_ptr()) };
+            assert_eq!(result, -1);
+        }
+        assert_eq!(
+            hhe_evaluate(hhe_hand_from_raw(hand.get_key(), hand.get_mask())),
+            hand.evaluate() as i32
+        );
+    }
+
+    /// Reinterprets a raw `hhe_hand` as C code could build it.
+    fn hhe_hand_from_raw(key: u64, mask: u64) -> Hand {
+        unsafe { std::mem::transmute([key, mask]) }
+    }
+}
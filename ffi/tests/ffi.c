@@ -0,0 +1,64 @@
+#include <stdio.h>
+#include "holdem_hand_evaluator.h"
+
+#define CHECK(cond)                                                  \
+    do {                                                             \
+        if (!(cond)) {                                               \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,   \
+                    __LINE__, #cond);                                \
+            return 1;                                                \
+        }                                                            \
+    } while (0)
+
+int main(void) {
+    /* AhKhQhJhTh8c6d */
+    const uint8_t royal[] = {50, 46, 42, 38, 34, 24, 17};
+    hhe_hand hand;
+    CHECK(hhe_hand_from_cards(royal, 7, &hand) == 0);
+    CHECK(hhe_hand_len(hand) == 7);
+    CHECK(hhe_evaluate(hand) == 32777);
+    CHECK(hhe_hand_category(32777) == HHE_STRAIGHT_FLUSH);
+
+    /* 2h2s3d3s4s5d6d */
+    hhe_hand straight = hhe_hand_new();
+    const uint8_t cards[] = {2, 3, 5, 7, 11, 13, 17};
+    for (size_t i = 0; i < sizeof(cards); ++i) {
+        straight = hhe_hand_add_card(straight, cards[i]);
+    }
+    CHECK(hhe_evaluate(straight) == 16385);
+    CHECK(hhe_hand_category(16385) == HHE_STRAIGHT);
+
+    /* invalid arguments */
+    const uint8_t duplicated[] = {0, 0};
+    CHECK(hhe_hand_from_cards(duplicated, 2, &hand) == -1);
+    CHECK(hhe_evaluate(hhe_hand_add_card(hhe_hand_new(), 0)) == -1);
+
+    /* AsAc vs. KsKc on 2d3d4d */
+    const uint8_t aces[] = {51, 48}, kings[] = {47, 44}, flop[] = {1, 5, 9};
+    hhe_hand hand1, hand2, board;
+    CHECK(hhe_hand_from_cards(aces, 2, &hand1) == 0);
+    CHECK(hhe_hand_from_cards(kings, 2, &hand2) == 0);
+    CHECK(hhe_hand_from_cards(flop, 3, &board) == 0);
+    hhe_win_frequency freq;
+    CHECK(hhe_heads_up_win_frequency(hand1, hand2, board, hhe_hand_new(), &freq) == 0);
+    CHECK(freq.win1 + freq.win2 + freq.tie == 990);
+    CHECK(freq.win1 > freq.win2);
+    CHECK(hhe_heads_up_win_frequency(hand1, hand1, board, hhe_hand_new(), &freq) == -1);
+
+    /* 5-card hand: exactly one category */
+    uint32_t counts[9];
+    hhe_hand flopped = hhe_hand_new();
+    for (size_t i = 0; i < 5; ++i) {
+        flopped = hhe_hand_add_card(flopped, royal[i]);
+    }
+    CHECK(hhe_enumerate_hand_category(flopped, hhe_hand_new(), counts) == 0);
+    uint32_t total = 0;
+    for (int i = 0; i < 9; ++i) {
+        total += counts[i];
+    }
+    CHECK(total == 1081);
+    CHECK(counts[HHE_STRAIGHT_FLUSH] == 1081);
+
+    printf("ok\n");
+    return 0;
+}
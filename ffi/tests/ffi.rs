@@ -0,0 +1,42 @@
+// builds `tests/ffi.c` against the static library with the system C compiler and runs it.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn test_c_program() {
+    // `cargo test` does not build the static library, so build it into a separate directory
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let exe = std::env::current_exe().unwrap();
+    let target_dir = exe.ancestors().nth(3).unwrap().join("ffi-test");
+    let status = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()))
+        .args(["build", "--lib", "--release", "--manifest-path"])
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success());
+    let library = target_dir.join("release/libholdem_hand_evaluator_ffi.a");
+
+    let output = std::env::temp_dir().join(format!("hhe_ffi_test_{}", std::process::id()));
+    let status = Command::new("cc")
+        .arg(manifest_dir.join("tests/ffi.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&output)
+        .status()
+        .expect("failed to run cc");
+    assert!(status.success());
+
+    let result = Command::new(&output).output().unwrap();
+    let _ = std::fs::remove_file(&output);
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&result.stdout), "ok\n");
+}
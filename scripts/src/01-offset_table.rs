@@ -1,120 +1,95 @@
 // generate an offset table for perfect hash function with the following form:
 //   hash_key = input_value + OFFSETS[input_value >> OFFSET_SHIFT];
-
-// reference: Z. J. Czech, G. Havas, and B. S. Majewski. "Perfect hashing".
-//            Theoretical Computer Science, 182(1-2), 1-143. 1997. (Section 5.2)
+//
+// usage: 01-offset_table [--sweep [--emit]]
+//   (no option): use `OFFSET_SHIFT` and first-fit-decreasing method
+//   --sweep: try various shift values and packing heuristics and report table sizes
+//   --emit: write the smallest result of the sweep (and update `OFFSET_SHIFT`)
 
 use assets::constants::*;
-use std::cmp::max;
-use std::fs::File;
+use holdem_hand_evaluator_scripts::*;
+use std::fs::{read_to_string, File};
 use std::io::Write;
 
-#[derive(Clone, Debug)]
-struct Row {
-    cols: Vec<u64>,
-    idx: usize,
-}
+/// size of `LOOKUP_FLUSH` in bytes including the padding entry (independent of the offset table)
+const LOOKUP_FLUSH_BYTES: usize = 8130 * 2;
 
 fn main() {
-    let offset_table_len = ((MAX_RANK_KEY >> OFFSET_SHIFT) + 1) as usize;
-    println!("size of offset table: {}", offset_table_len);
-
-    let mut keys = Vec::new();
-    for i in 0..(NUMBER_OF_RANKS - 1) {
-        for j in i..NUMBER_OF_RANKS {
-            for k in j..NUMBER_OF_RANKS {
-                for m in k..NUMBER_OF_RANKS {
-                    for n in max(m, i + 1)..NUMBER_OF_RANKS {
-                        let x = RANK_BASES[i] + RANK_BASES[j] + RANK_BASES[k];
-                        let x = x + RANK_BASES[m] + RANK_BASES[n];
-                        keys.push(x);
-                        for p in max(n, j + 1)..NUMBER_OF_RANKS {
-                            let x = x + RANK_BASES[p];
-                            keys.push(x);
-                            for q in max(p, k + 1)..NUMBER_OF_RANKS {
-                                let x = x + RANK_BASES[q];
-                                keys.push(x);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let sweep = args.iter().any(|a| a == "--sweep");
+    let emit = args.iter().any(|a| a == "--emit");
 
+    let keys = rank_keys();
     println!("number of elements: {}", keys.len());
 
-    let mut rows: Vec<Row> = vec![
-        Row {
-            cols: Vec::new(),
-            idx: 0
-        };
-        offset_table_len
-    ];
-
-    for (i, row) in rows.iter_mut().enumerate() {
-        row.idx = i;
-    }
-
-    for key in &keys {
-        let row = key >> OFFSET_SHIFT;
-        let col = key & ((1 << OFFSET_SHIFT) - 1);
-        rows[row as usize].cols.push(col);
-    }
-
-    for row in &mut rows {
-        row.cols.sort_unstable();
+    if !sweep {
+        let offset_table_len = ((MAX_RANK_KEY >> OFFSET_SHIFT) + 1) as usize;
+        println!("size of offset table: {}", offset_table_len);
+        let (offsets, image_size) = generate_offsets(&keys);
+        println!("image size: {}", image_size);
+        write_offsets(&offsets);
+        return;
     }
 
-    // apply first-fit-decreasing method
-    rows.sort_by_key(|row| row.cols.len());
-    rows.reverse();
-
-    let mut least_empty = 0;
-    let mut filled = vec![false; offset_table_len << OFFSET_SHIFT];
-    let mut offsets = vec![i32::MIN; rows.len()];
-
-    for Row { cols, idx } in &rows {
-        if cols.is_empty() {
-            break;
-        }
-        let mut offset = least_empty as i32 - cols[0] as i32;
-        'search: for i in offset.. {
-            for col in cols {
-                if filled[col.wrapping_add(i as u64) as usize] {
-                    continue 'search;
-                }
+    println!();
+    println!("shift  packing        OFFSETS  LOOKUP  total (KB)");
+    let mut best: Option<(usize, usize, Vec<i32>)> = None;
+    for shift in 8..=16 {
+        let packings = [
+            ("first-fit", Packing::FirstFit),
+            ("best-fit", Packing::BestFit),
+            (
+                "random-restart",
+                Packing::RandomRestart {
+                    restarts: 20,
+                    seed: shift as u64,
+                },
+            ),
+        ];
+        for (name, packing) in packings.iter() {
+            let (offsets, image_size) = generate_offsets_with(&keys, shift, *packing);
+            let total = offsets.len() * 4 + (image_size + 1) * 2 + LOOKUP_FLUSH_BYTES;
+            println!(
+                "{:5}  {:14} {:7}  {:6}  {:10.1}",
+                shift,
+                name,
+                offsets.len(),
+                image_size,
+                total as f64 / 1024.0
+            );
+            if best.as_ref().map_or(true, |b| total < b.0) {
+                best = Some((total, shift, offsets));
             }
-            offset = i;
-            break;
-        }
-        offsets[*idx] = offset;
-        for col in cols {
-            filled[col.wrapping_add(offset as u64) as usize] = true;
-        }
-        while filled[least_empty] {
-            least_empty += 1;
         }
     }
 
-    for (i, offset) in offsets.iter_mut().enumerate() {
-        *offset = match *offset {
-            i32::MIN => 0,
-            _ => *offset - (i << OFFSET_SHIFT) as i32,
-        }
+    let (total, shift, offsets) = best.unwrap();
+    println!();
+    println!(
+        "smallest: shift = {} ({:.1} KB; current: shift = {})",
+        shift,
+        total as f64 / 1024.0,
+        OFFSET_SHIFT
+    );
+
+    if emit {
+        let path = "assets/src/constants.rs";
+        let constants = read_to_string(path).unwrap();
+        let old = format!("pub const OFFSET_SHIFT: usize = {};", OFFSET_SHIFT);
+        let new = format!("pub const OFFSET_SHIFT: usize = {};", shift);
+        assert!(constants.contains(&old));
+        File::create(path)
+            .unwrap()
+            .write_all(constants.replace(&old, &new).as_bytes())
+            .unwrap();
+        println!("wrote OFFSET_SHIFT to '{}'", path);
+        write_offsets(&offsets);
+        println!("run 02-lookup_tables to regenerate the lookup tables");
     }
+}
 
-    let image_size = filled.iter().rposition(|&b| b).unwrap() + 1;
-    println!("image size: {}", image_size);
-
+fn write_offsets(offsets: &[i32]) {
     let mut file = File::create("assets/src/offsets.rs").unwrap();
-    writeln!(
-        file,
-        "pub const OFFSETS: [i32; {}] = {:?};",
-        offsets.len(),
-        offsets
-    )
-    .unwrap();
-
+    write!(file, "{}", offsets_source(offsets)).unwrap();
     println!("wrote result to 'assets/src/offsets.rs'");
 }
@@ -0,0 +1,145 @@
+// search rank bases that guarantee a unique sum for every rank combination of 5-7 cards.
+//
+// bases are chosen greedily from the deuce: each base is the smallest value larger than the
+// previous one such that every rank combination of up to 7 cards (at most 4 of each rank) still
+// has a unique sum. randomized trials skip some valid candidates to explore other solutions.
+// the resulting `MAX_RANK_KEY` and table sizes are reported for comparison with `RANK_BASES`.
+//
+// usage: 03-rank_bases [number of randomized trials (default: 5)]
+
+use assets::constants::*;
+use holdem_hand_evaluator_scripts::*;
+
+/// size of `LOOKUP_FLUSH` in bytes including the padding entry (independent of the rank bases)
+const LOOKUP_FLUSH_BYTES: usize = 8130 * 2;
+
+struct BitSet(Vec<u64>);
+
+impl BitSet {
+    fn contains(&self, x: u64) -> bool {
+        let word = (x / 64) as usize;
+        word < self.0.len() && (self.0[word] >> (x % 64)) & 1 != 0
+    }
+
+    fn insert(&mut self, x: u64) {
+        let word = (x / 64) as usize;
+        if word >= self.0.len() {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << (x % 64);
+    }
+
+    fn remove(&mut self, x: u64) {
+        self.0[(x / 64) as usize] &= !(1 << (x % 64));
+    }
+}
+
+/// Searches rank bases greedily; each valid candidate is skipped with probability
+/// `skip_rate` / 256.
+fn search(rng: &mut SplitMix64, skip_rate: u64) -> [u64; NUMBER_OF_RANKS] {
+    let mut bases = [0; NUMBER_OF_RANKS];
+    // (sum, number of cards) of all rank combinations of the ranks so far
+    let mut sums = vec![(0, 0)];
+    let mut used = BitSet(Vec::new());
+    used.insert(0);
+
+    for rank in 0..NUMBER_OF_RANKS {
+        let mut base = match rank {
+            0 => 1,
+            _ => bases[rank - 1] + 1,
+        };
+        let mut added = Vec::new();
+        loop {
+            let is_valid = 'check: {
+                for &(sum, num_cards) in &sums {
+                    for count in 1..=4.min(7 - num_cards) {
+                        let x = sum + count as u64 * base;
+                        if used.contains(x) {
+                            break 'check false;
+                        }
+                        used.insert(x);
+                        added.push((x, num_cards + count));
+                    }
+                }
+                true
+            };
+            if is_valid && rng.next() % 256 >= skip_rate {
+                break;
+            }
+            for (x, _) in added.drain(..) {
+                used.remove(x);
+            }
+            base += 1;
+        }
+        bases[rank] = base;
+        sums.extend(added);
+    }
+    bases
+}
+
+/// Checks whether all rank combinations of 5-7 cards have unique sums.
+fn is_unique(rank_bases: &[u64; NUMBER_OF_RANKS]) -> bool {
+    let mut keys = rank_keys_with(rank_bases);
+    let len = keys.len();
+    keys.sort_unstable();
+    keys.dedup();
+    keys.len() == len
+}
+
+/// Prints the bases and the smallest table size over shift values and packing heuristics.
+fn report(name: &str, rank_bases: &[u64; NUMBER_OF_RANKS]) {
+    let keys = rank_keys_with(rank_bases);
+    let max_key = *keys.iter().max().unwrap();
+    println!("{}:", name);
+    println!("  bases: {:#x?}", rank_bases);
+    println!(
+        "  unique: {}, MAX_RANK_KEY: {:#x} ({} bits)",
+        is_unique(rank_bases),
+        max_key,
+        64 - max_key.leading_zeros()
+    );
+
+    let max_shift = 64 - max_key.leading_zeros() as usize;
+    let mut best: Option<(usize, usize, usize, usize)> = None;
+    for shift in max_shift.saturating_sub(16)..=max_shift.saturating_sub(10) {
+        for packing in [Packing::FirstFit, Packing::BestFit].iter() {
+            let (offsets, image_size) = generate_offsets_with(&keys, shift, *packing);
+            let total = offsets.len() * 4 + (image_size + 1) * 2 + LOOKUP_FLUSH_BYTES;
+            if best.map_or(true, |b| total < b.0) {
+                best = Some((total, shift, offsets.len(), image_size));
+            }
+        }
+    }
+    let (total, shift, offsets_len, image_size) = best.unwrap();
+    println!(
+        "  smallest tables: shift = {}, OFFSETS: {}, LOOKUP: {}, total: {:.1} KB",
+        shift,
+        offsets_len,
+        image_size,
+        total as f64 / 1024.0
+    );
+}
+
+fn main() {
+    let num_trials = std::env::args()
+        .nth(1)
+        .map_or(5, |arg| arg.parse().expect("invalid number of trials"));
+
+    report("current RANK_BASES", &RANK_BASES);
+
+    let mut rng = SplitMix64(0);
+    let greedy = search(&mut rng, 0);
+    report("greedy", &greedy);
+
+    let mut best = greedy;
+    for _ in 0..num_trials {
+        let bases = search(&mut rng, 32);
+        let max_key = |b: &[u64; NUMBER_OF_RANKS]| 4 * b[12] + 3 * b[11];
+        if max_key(&bases) < max_key(&best) {
+            best = bases;
+        }
+    }
+    if best != greedy {
+        report("randomized (best MAX_RANK_KEY)", &best);
+    }
+}
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::identity_op)]
 mod tests {
     use crate::kev::eval_5cards;
 
@@ -0,0 +1,528 @@
+// generation logic of the assets.
+// used by the binaries in this crate and by the build script of the main crate.
+
+#![allow(clippy::needless_range_loop)]
+
+#[cfg(test)]
+mod kev;
+
+pub use assets;
+
+use assets::constants::*;
+use std::cmp::max;
+
+/// Returns the rank keys of all rank combinations of 5-7 cards.
+pub fn rank_keys() -> Vec<u64> {
+    rank_keys_with(&RANK_BASES)
+}
+
+/// Returns the rank keys of all rank combinations of 5-7 cards for the given rank bases.
+pub fn rank_keys_with(rank_bases: &[u64; NUMBER_OF_RANKS]) -> Vec<u64> {
+    let mut keys = Vec::new();
+    for_each_rank_combination(|ranks| keys.push(ranks.iter().map(|&r| rank_bases[r]).sum()));
+    keys
+}
+
+/// Calls `f` with the ranks (in ascending order) of every rank combination of 5-7 cards, where
+/// each rank appears at most four times.
+pub fn for_each_rank_combination(mut f: impl FnMut(&[usize])) {
+    for i in 0..(NUMBER_OF_RANKS - 1) {
+        for j in i..NUMBER_OF_RANKS {
+            for k in j..NUMBER_OF_RANKS {
+                for m in k..NUMBER_OF_RANKS {
+                    for n in max(m, i + 1)..NUMBER_OF_RANKS {
+                        f(&[i, j, k, m, n]);
+                        for p in max(n, j + 1)..NUMBER_OF_RANKS {
+                            f(&[i, j, k, m, n, p]);
+                            for q in max(p, k + 1)..NUMBER_OF_RANKS {
+                                f(&[i, j, k, m, n, p, q]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Packing heuristic of the offset table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Packing {
+    /// First-fit decreasing: rows are placed in decreasing order of size at the first offset
+    /// without conflicts.
+    FirstFit,
+    /// Best-fit decreasing: among the first 64 offsets without conflicts that do not extend the
+    /// image, rows are placed where the most cells in their span are already filled.
+    BestFit,
+    /// First-fit decreasing with random tie-breaking among rows of the same size; the smallest
+    /// result of `restarts` trials is kept.
+    RandomRestart { restarts: usize, seed: u64 },
+}
+
+/// Generates an offset table for perfect hash function with the following form:
+///   hash_key = input_value + OFFSETS[input_value >> OFFSET_SHIFT];
+/// Return value: (offset table, image size of the hash function)
+///
+/// reference: Z. J. Czech, G. Havas, and B. S. Majewski. "Perfect hashing".
+///            Theoretical Computer Science, 182(1-2), 1-143. 1997. (Section 5.2)
+pub fn generate_offsets(keys: &[u64]) -> (Vec<i32>, usize) {
+    generate_offsets_with(keys, OFFSET_SHIFT, Packing::FirstFit)
+}
+
+/// Same as `generate_offsets()`, but with the given shift value and packing heuristic.
+pub fn generate_offsets_with(keys: &[u64], shift: usize, packing: Packing) -> (Vec<i32>, usize) {
+    let offset_table_len = ((keys.iter().max().unwrap() >> shift) + 1) as usize;
+
+    let mut rows = vec![Vec::new(); offset_table_len];
+    for key in keys {
+        rows[(key >> shift) as usize].push(key & ((1 << shift) - 1));
+    }
+    for row in &mut rows {
+        row.sort_unstable();
+    }
+
+    // decreasing order of row size (ties are broken by descending index)
+    let mut order = (0..offset_table_len).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| rows[idx].len());
+    order.reverse();
+
+    match packing {
+        Packing::FirstFit => pack(&rows, &order, shift, false),
+        Packing::BestFit => pack(&rows, &order, shift, true),
+        Packing::RandomRestart { restarts, seed } => {
+            let mut rng = SplitMix64(seed);
+            let mut best = pack(&rows, &order, shift, false);
+            for _ in 1..restarts {
+                for i in (1..order.len()).rev() {
+                    let j = (rng.next() % (i as u64 + 1)) as usize;
+                    order.swap(i, j);
+                }
+                order.sort_by_key(|&idx| std::cmp::Reverse(rows[idx].len()));
+                let result = pack(&rows, &order, shift, false);
+                if result.1 < best.1 {
+                    best = result;
+                }
+            }
+            best
+        }
+    }
+}
+
+fn pack(rows: &[Vec<u64>], order: &[usize], shift: usize, best_fit: bool) -> (Vec<i32>, usize) {
+    let mut least_empty = 0;
+    let mut image_size = 0;
+    let mut filled = vec![0u64; ((rows.len() << shift) >> 6) + 2];
+    let mut offsets = vec![i32::MIN; rows.len()];
+
+    for &idx in order {
+        let cols = &rows[idx];
+        if cols.is_empty() {
+            break;
+        }
+        let start = least_empty as i64 - cols[0] as i64;
+        let offset = match best_fit {
+            true => find_best_fit(&filled, cols, start, image_size),
+            false => None,
+        };
+        let offset = offset.unwrap_or_else(|| find_first_fit(&filled, cols, start));
+        offsets[idx] = offset as i32;
+        for &col in cols {
+            let pos = (col as i64 + offset) as usize;
+            filled[pos / 64] |= 1 << (pos % 64);
+            image_size = image_size.max(pos + 1);
+        }
+        least_empty = next_empty(&filled, least_empty);
+    }
+
+    for (i, offset) in offsets.iter_mut().enumerate() {
+        *offset = match *offset {
+            i32::MIN => 0,
+            _ => *offset - (i << shift) as i32,
+        }
+    }
+    (offsets, image_size)
+}
+
+/// Returns the bit mask of offsets in \[`offset`, `offset` + 64) where some column conflicts.
+#[inline]
+fn rejected_offsets(filled: &[u64], cols: &[u64], offset: i64) -> u64 {
+    let mut rejected = 0;
+    for &col in cols {
+        rejected |= window(filled, (col as i64 + offset) as usize);
+        if rejected == !0 {
+            break;
+        }
+    }
+    rejected
+}
+
+/// Returns the first offset at or after `start` without conflicts.
+fn find_first_fit(filled: &[u64], cols: &[u64], start: i64) -> i64 {
+    let mut offset = start;
+    loop {
+        let rejected = rejected_offsets(filled, cols, offset);
+        if rejected != !0 {
+            return offset + rejected.trailing_ones() as i64;
+        }
+        offset += 64;
+    }
+}
+
+/// Returns the offset where the most cells in the span of the row are already filled, among the
+/// first 64 offsets without conflicts that do not extend the image.
+fn find_best_fit(filled: &[u64], cols: &[u64], start: i64, image_size: usize) -> Option<i64> {
+    let last = *cols.last().unwrap() as i64;
+    let mut best: Option<(u32, i64)> = None;
+    let mut num_candidates = 0;
+    let mut offset = start;
+    while num_candidates < 64 && offset + last < image_size as i64 {
+        let rejected = rejected_offsets(filled, cols, offset);
+        for t in (0..64).filter(|t| (rejected >> t) & 1 == 0) {
+            let o = offset + t;
+            if o + last >= image_size as i64 || num_candidates == 64 {
+                break;
+            }
+            let span = ((cols[0] as i64 + o) as usize, (last + o) as usize);
+            let score = count_filled(filled, span.0, span.1);
+            if best.map_or(true, |(s, _)| score > s) {
+                best = Some((score, o));
+            }
+            num_candidates += 1;
+        }
+        offset += 64;
+    }
+    best.map(|(_, o)| o)
+}
+
+/// Returns the 64 bits of `filled` starting at `pos`.
+#[inline]
+fn window(filled: &[u64], pos: usize) -> u64 {
+    let (word, bit) = (pos / 64, pos % 64);
+    match bit {
+        0 => filled[word],
+        _ => (filled[word] >> bit) | (filled[word + 1] << (64 - bit)),
+    }
+}
+
+/// Returns the first empty position at or after `pos`.
+fn next_empty(filled: &[u64], pos: usize) -> usize {
+    let mut word = pos / 64;
+    let mut bits = !filled[word] & (!0 << (pos % 64));
+    while bits == 0 {
+        word += 1;
+        bits = !filled[word];
+    }
+    word * 64 + bits.trailing_zeros() as usize
+}
+
+/// Returns the number of filled positions in \[`lo`, `hi`\].
+fn count_filled(filled: &[u64], lo: usize, hi: usize) -> u32 {
+    (lo..=hi)
+        .step_by(64)
+        .map(|pos| {
+            let bits = window(filled, pos);
+            match hi - pos {
+                d if d >= 63 => bits.count_ones(),
+                d => (bits & ((2 << d) - 1)).count_ones(),
+            }
+        })
+        .sum()
+}
+
+/// Simple pseudorandom number generator used by the search scripts.
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    /// Returns the next pseudorandom value.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let z = self.0;
+        let z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Returns the top rank of the best straight in `rankset` (3 for the wheel).
+fn straight_top(rankset: u16) -> Option<usize> {
+    let extended = (rankset << 1) | (rankset >> 12);
+    (3..NUMBER_OF_RANKS)
+        .rev()
+        .find(|top| (extended >> (top - 3)) & 0b11111 == 0b11111)
+}
+
+/// Returns the set of the highest `n` ranks in `rankset`.
+fn top_ranks(rankset: u16, n: usize) -> u16 {
+    let mut rankset = rankset;
+    while rankset.count_ones() as usize > n {
+        rankset &= rankset - 1;
+    }
+    rankset
+}
+
+/// Returns the colexicographic index of `rankset` among the subsets of the same size
+/// (this coincides with the order of hand strength).
+fn colex_index(rankset: u16) -> u16 {
+    let mut index = 0;
+    let mut k = 0;
+    for rank in 0..16 {
+        if (rankset >> rank) & 1 != 0 {
+            k += 1;
+            index += binomial(rank, k);
+        }
+    }
+    index
+}
+
+fn binomial(n: usize, k: usize) -> u16 {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1)) as u16
+}
+
+/// Removes the ranks in `removed` from `rankset` and compresses the remaining ranks.
+fn compress(rankset: u16, removed: u16) -> u16 {
+    let mut result = 0;
+    let mut bit = 0;
+    for rank in 0..NUMBER_OF_RANKS {
+        if (removed >> rank) & 1 == 0 {
+            result |= ((rankset >> rank) & 1) << bit;
+            bit += 1;
+        }
+    }
+    result
+}
+
+/// Index of five distinct ranks among the 1,277 non-straight combinations.
+fn high_card_index(rankset: u16) -> u16 {
+    let wheel = 0b1_0000_0000_1111;
+    let num_straights_below = (0..=8)
+        .map(|i| 0b11111 << i)
+        .chain(std::iter::once(wheel))
+        .filter(|&s| s < rankset)
+        .count() as u16;
+    colex_index(rankset) - num_straights_below
+}
+
+/// Returns the hand strength of 5-7 cards of `ranks` without a flush, computed directly from
+/// the rank counts.
+pub fn non_flush_rank(ranks: &[usize]) -> u16 {
+    let mut counts = [0; NUMBER_OF_RANKS];
+    for &r in ranks {
+        counts[r] += 1;
+    }
+    let set = |min: usize| {
+        (0..NUMBER_OF_RANKS)
+            .filter(|&r| counts[r] >= min)
+            .fold(0u16, |acc, r| acc | (1 << r))
+    };
+    let highest = |rankset: u16| 15 - rankset.leading_zeros() as usize;
+    let (any, pairs, trips, quads) = (set(1), set(2), set(3), set(4));
+
+    if quads != 0 {
+        let q = highest(quads);
+        let kicker = top_ranks(any & !(1 << q), 1);
+        return (7 << 12) + q as u16 * 12 + colex_index(compress(kicker, 1 << q));
+    }
+    if trips != 0 {
+        let t = highest(trips);
+        let rest = pairs & !(1 << t);
+        if rest != 0 {
+            let pair = top_ranks(rest, 1);
+            return (6 << 12) + t as u16 * 12 + colex_index(compress(pair, 1 << t));
+        }
+    }
+    if let Some(top) = straight_top(any) {
+        return (4 << 12) + top as u16 - 3;
+    }
+    if trips != 0 {
+        let t = highest(trips);
+        let kickers = top_ranks(any & !(1 << t), 2);
+        return (3 << 12) + t as u16 * 66 + colex_index(compress(kickers, 1 << t));
+    }
+    if pairs.count_ones() >= 2 {
+        let two_pairs = top_ranks(pairs, 2);
+        let kicker = top_ranks(any & !two_pairs, 1);
+        return (2 << 12) + colex_index(two_pairs) * 11 + colex_index(compress(kicker, two_pairs));
+    }
+    if pairs != 0 {
+        let p = highest(pairs);
+        let kickers = top_ranks(any & !(1 << p), 3);
+        return (1 << 12) + p as u16 * 220 + colex_index(compress(kickers, 1 << p));
+    }
+    high_card_index(top_ranks(any, 5))
+}
+
+/// Returns the hand strength of a flush of 5-7 cards of the ranks in `rankset`.
+pub fn flush_rank(rankset: u16) -> u16 {
+    match straight_top(rankset) {
+        Some(top) => (8 << 12) + top as u16 - 3,
+        None => (5 << 12) + high_card_index(top_ranks(rankset, 5)),
+    }
+}
+
+/// Generates lookup tables `(LOOKUP, LOOKUP_FLUSH)` for `offsets`.
+/// The hand strength without a flush depends only on the rank counts, and that of a flush only
+/// on the ranks of the flush suit, so each entry is computed directly from them (this is fast
+/// enough for the `runtime-tables` feature; see the tests for the check against Cactus Kev's
+/// evaluator). Each table ends with one padding entry, so that the 32-bit gathers of the AVX2
+/// evaluator stay in bounds. Returns `None` if `offsets` does not give a perfect hash function
+/// for the rank keys.
+pub fn generate_lookup_tables(offsets: &[i32]) -> Option<(Vec<u16>, Vec<u16>)> {
+    let image_len = offsets.len() << OFFSET_SHIFT;
+    // rank key (plus 1) stored in each slot of `lookup` to detect hash collisions
+    let mut keys = vec![0u32; image_len];
+    let mut lookup = vec![0u16; image_len];
+    let mut collision = false;
+
+    for_each_rank_combination(|ranks| {
+        let key = ranks.iter().map(|&r| RANK_BASES[r]).sum::<u64>() as usize;
+        let offset = offsets.get(key >> OFFSET_SHIFT);
+        let hash_key = offset.map_or(usize::MAX, |&o| key.wrapping_add(o as usize));
+        match keys.get(hash_key) {
+            Some(0) => {
+                keys[hash_key] = key as u32 + 1;
+                lookup[hash_key] = non_flush_rank(ranks);
+            }
+            _ => collision = true,
+        }
+    });
+
+    if collision {
+        return None;
+    }
+
+    // the flush table is indexed by the ranks of the flush suit
+    let mut lookup_flush = vec![0u16; 1 << NUMBER_OF_RANKS];
+    for (rankset, entry) in lookup_flush.iter_mut().enumerate() {
+        if (5..=7).contains(&rankset.count_ones()) {
+            *entry = flush_rank(rankset as u16);
+        }
+    }
+
+    let lookup_len = keys.iter().rposition(|&k| k != 0).unwrap() + 1;
+    let flush_len = lookup_flush.iter().rposition(|&v| v != 0).unwrap() + 1;
+    lookup.truncate(lookup_len);
+    lookup_flush.truncate(flush_len);
+    lookup.push(0);
+    lookup_flush.push(0);
+    Some((lookup, lookup_flush))
+}
+
+/// Returns Rust source code that defines `OFFSETS`.
+pub fn offsets_source(offsets: &[i32]) -> String {
+    format!(
+        "pub const OFFSETS: [i32; {}] = {:?};\n",
+        offsets.len(),
+        offsets
+    )
+}
+
+/// Returns Rust source code that defines `LOOKUP` and `LOOKUP_FLUSH`.
+pub fn lookup_tables_source(lookup: &[u16], lookup_flush: &[u16]) -> String {
+    format!(
+        "pub const LOOKUP: [u16; {}] = {:?};\n\npub const LOOKUP_FLUSH: [u16; {}] = {:?};\n",
+        lookup.len(),
+        lookup,
+        lookup_flush.len(),
+        lookup_flush
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adjust_hand_rank(rank: u16) -> u16 {
+        let reversed_rank = 7463 - rank; // now best hand = 7462
+        match reversed_rank {
+            1..=1277 => reversed_rank - 1,                   // 1277 high card
+            1278..=4137 => (1 << 12) + reversed_rank - 1278, // 2860 one pair
+            4138..=4995 => (2 << 12) + reversed_rank - 4138, //  858 two pair
+            4996..=5853 => (3 << 12) + reversed_rank - 4996, //  858 three-kind
+            5854..=5863 => (4 << 12) + reversed_rank - 5854, //   10 straights
+            5864..=7140 => (5 << 12) + reversed_rank - 5864, // 1277 flushes
+            7141..=7296 => (6 << 12) + reversed_rank - 7141, //  156 full house
+            7297..=7452 => (7 << 12) + reversed_rank - 7297, //  156 four-kind
+            7453..=7462 => (8 << 12) + reversed_rank - 7453, //   10 straight flushes
+            _ => panic!(),
+        }
+    }
+
+    /// Evaluates 5-7 cards with Cactus Kev's evaluator.
+    fn eval_cards(c: &[usize]) -> u16 {
+        match *c {
+            [c1, c2, c3, c4, c5] => kev::eval_5cards(c1, c2, c3, c4, c5),
+            [c1, c2, c3, c4, c5, c6] => kev::eval_6cards(c1, c2, c3, c4, c5, c6),
+            [c1, c2, c3, c4, c5, c6, c7] => kev::eval_7cards(c1, c2, c3, c4, c5, c6, c7),
+            _ => panic!("expected 5-7 cards"),
+        }
+    }
+
+    #[test]
+    fn test_direct_ranks() {
+        for_each_rank_combination(|ranks| {
+            // suits are assigned cyclically: cards of the same rank get distinct suits, and no
+            // suit has more than two cards
+            let cards = ranks
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| 4 * r + i % 4)
+                .collect::<Vec<_>>();
+            assert_eq!(non_flush_rank(ranks), adjust_hand_rank(eval_cards(&cards)));
+        });
+        for rankset in 0u16..(1 << NUMBER_OF_RANKS) {
+            if (5..=7).contains(&rankset.count_ones()) {
+                let cards = (0..NUMBER_OF_RANKS)
+                    .filter(|r| (rankset >> r) & 1 != 0)
+                    .map(|r| 4 * r)
+                    .collect::<Vec<_>>();
+                assert_eq!(flush_rank(rankset), adjust_hand_rank(eval_cards(&cards)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_committed_tables() {
+        let (lookup, lookup_flush) = generate_lookup_tables(&assets::offsets::OFFSETS).unwrap();
+        assert_eq!(lookup[..], assets::lookup::LOOKUP[..]);
+        assert_eq!(lookup_flush[..], assets::lookup::LOOKUP_FLUSH[..]);
+
+        let keys = rank_keys();
+        assert_eq!(keys.len(), 73775);
+        let (offsets, image_size) = generate_offsets(&keys);
+        let (lookup, _) = generate_lookup_tables(&offsets).unwrap();
+        assert_eq!(lookup.len(), image_size + 1);
+
+        // other packing heuristics must also give perfect hash functions
+        let packings = [
+            Packing::BestFit,
+            Packing::RandomRestart {
+                restarts: 3,
+                seed: 0,
+            },
+        ];
+        for packing in packings.iter() {
+            for shift in 10..=12 {
+                let (offsets, image_size) = generate_offsets_with(&keys, shift, *packing);
+                let mut used = vec![false; image_size];
+                for key in &keys {
+                    let hash_key = key.wrapping_add(offsets[(key >> shift) as usize] as u64);
+                    assert!(!used[hash_key as usize]);
+                    used[hash_key as usize] = true;
+                }
+                if shift == OFFSET_SHIFT && *packing == Packing::BestFit {
+                    assert_eq!(image_size, keys.len());
+                }
+            }
+        }
+
+        // every row is mapped to the same range
+        let broken = (0..offsets.len())
+            .map(|i| -((i << OFFSET_SHIFT) as i32))
+            .collect::<Vec<_>>();
+        assert!(generate_lookup_tables(&broken).is_none());
+    }
+}
@@ -0,0 +1,350 @@
+use crate::hand::*;
+use assets::constants::*;
+
+/// Returns Badugi hand strength of `hand` (1-4 cards) in 16-bit integer; stronger hand yields
+/// higher value, so two hands can be compared directly.
+/// The best subset of cards with distinct suits and distinct ranks is used (aces are low):
+/// more cards is better, and then the lower highest card is better. (A-2-3-4 is the best)
+#[inline]
+pub fn evaluate_badugi(hand: &Hand) -> u16 {
+    best_badugi(hand.get_mask()).0
+}
+
+/// Returns the number of cards used in Badugi hand strength computed by `evaluate_badugi()`.
+#[inline]
+pub fn get_badugi_size(badugi_rank: u16) -> usize {
+    (badugi_rank >> 13) as usize
+}
+
+/// Returns the cards of `hand` that form the best Badugi (i.e., the cards to keep when drawing).
+pub fn best_badugi_cards(hand: &Hand) -> Hand {
+    let submask = best_badugi(hand.get_mask()).1;
+    let mut result = *hand;
+    for (card, &(_, card_mask)) in CARDS.iter().enumerate() {
+        if hand.contains(card) && (card_mask & submask) == 0 {
+            result = result.remove_card(card);
+        }
+    }
+    result
+}
+
+fn best_badugi(mask: u64) -> (u16, u64) {
+    let mut best = (0, 0);
+    let mut submask = mask;
+    while submask != 0 {
+        let suits = [
+            submask as u16,
+            (submask >> 16) as u16,
+            (submask >> 32) as u16,
+            (submask >> 48) as u16,
+        ];
+        let rankset = suits[0] | suits[1] | suits[2] | suits[3];
+        let num_cards = submask.count_ones();
+        if suits.iter().all(|&s| (s & s.wrapping_sub(1)) == 0) && rankset.count_ones() == num_cards
+        {
+            // bit 0 = ace, bit 1 = deuce, ..., bit 12 = king
+            let lowset = ((rankset << 1) | (rankset >> 12)) & 0x1fff;
+            let value = ((num_cards as u16) << 13) | (!lowset & 0x1fff);
+            if value > best.0 {
+                best = (value, submask);
+            }
+        }
+        submask = (submask - 1) & mask;
+    }
+    best
+}
+
+/// Computes exact heads-up win probabilities of Badugi with `num_draws` draws remaining.
+/// On the first draw, each player draws cards up to 4 cards (`hand1` and `hand2` are the kept
+/// cards); on later draws, each player keeps `best_badugi_cards()` and draws the rest.
+/// Discarded cards are dead, and `hand1` draws first in each round. All draw outcomes of a
+/// round are equally likely, so each subtree is weighted by the inverse of their number.
+/// Return value: (probability of `hand1` win, probability of `hand2` win, probability of tie)
+///
+/// Every runout is enumerated, so `num_draws` is limited to 1 or 2: two draws of one card each
+/// make about (45 * 44)^2 = 3.9M runouts, and three draws would make about 7.8G.
+///
+/// Panics if the alive cards cannot cover the draws in the worst case, i.e., if there are fewer
+/// than `8 - hand1.len() - hand2.len()` alive cards for the first draw plus 6 for the second.
+pub fn badugi_draw_win_frequency(
+    hand1: &Hand,
+    hand2: &Hand,
+    dead_cards: &Hand,
+    num_draws: usize,
+) -> (f64, f64, f64) {
+    assert!(hand1.len() <= 4 && hand2.len() <= 4);
+    assert!(
+        (1..=2).contains(&num_draws),
+        "num_draws must be 1 or 2, but got {}",
+        num_draws
+    );
+    let used_mask = hand1.get_mask() | hand2.get_mask() | dead_cards.get_mask();
+    assert_eq!(
+        used_mask.count_ones() as usize,
+        hand1.len() + hand2.len() + dead_cards.len()
+    );
+    let alive_cards = (0..NUMBER_OF_CARDS)
+        .filter(|&c| (CARDS[c].1 & used_mask) == 0)
+        .collect::<Vec<_>>();
+    // each player keeps at least one card after the first draw
+    let max_drawn = 8 - hand1.len() - hand2.len() + 6 * (num_draws - 1);
+    assert!(
+        alive_cards.len() >= max_drawn,
+        "{} alive cards cannot cover the draws, which need up to {} cards",
+        alive_cards.len(),
+        max_drawn
+    );
+    badugi_draw(hand1, hand2, &alive_cards, used_mask, num_draws)
+}
+
+fn badugi_draw(
+    hand1: &Hand,
+    hand2: &Hand,
+    alive_cards: &[usize],
+    used_mask: u64,
+    num_draws: usize,
+) -> (f64, f64, f64) {
+    if num_draws == 0 {
+        let rank1 = evaluate_badugi(hand1);
+        let rank2 = evaluate_badugi(hand2);
+        return if rank1 > rank2 {
+            (1.0, 0.0, 0.0)
+        } else if rank1 < rank2 {
+            (0.0, 1.0, 0.0)
+        } else {
+            (0.0, 0.0, 1.0)
+        };
+    }
+    let mut sum = (0.0, 0.0, 0.0);
+    let mut count = 0;
+    for_each_draw(hand1, alive_cards, used_mask, 0, &mut |hand1, used_mask| {
+        for_each_draw(hand2, alive_cards, used_mask, 0, &mut |hand2, used_mask| {
+            let (next1, next2) = match num_draws {
+                1 => (*hand1, *hand2),
+                _ => (best_badugi_cards(hand1), best_badugi_cards(hand2)),
+            };
+            let p = badugi_draw(&next1, &next2, alive_cards, used_mask, num_draws - 1);
+            sum.0 += p.0;
+            sum.1 += p.1;
+            sum.2 += p.2;
+            count += 1;
+        });
+    });
+    let count = count as f64;
+    (sum.0 / count, sum.1 / count, sum.2 / count)
+}
+
+fn for_each_draw(
+    hand: &Hand,
+    alive_cards: &[usize],
+    used_mask: u64,
+    start: usize,
+    f: &mut dyn FnMut(&Hand, u64),
+) {
+    if hand.len() == 4 {
+        f(hand, used_mask);
+        return;
+    }
+    for i in start..alive_cards.len() {
+        let card_mask = CARDS[alive_cards[i]].1;
+        if (used_mask & card_mask) == 0 {
+            let hand = hand.add_card(alive_cards[i]);
+            for_each_draw(&hand, alive_cards, used_mask | card_mask, i + 1, f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate_badugi_naive(cards: &[usize]) -> u16 {
+        let mut best: (usize, Vec<usize>) = (0, Vec::new());
+        for subset in 1..(1 << cards.len()) {
+            let chosen = (0..cards.len())
+                .filter(|i| (subset >> i) & 1 != 0)
+                .map(|i| cards[i])
+                .collect::<Vec<_>>();
+            let mut ok = true;
+            for i in 0..chosen.len() {
+                for j in (i + 1)..chosen.len() {
+                    ok &= chosen[i] / 4 != chosen[j] / 4 && chosen[i] % 4 != chosen[j] % 4;
+                }
+            }
+            if !ok {
+                continue;
+            }
+            // ace-low ranks in descending order
+            let mut ranks = chosen.iter().map(|c| (c / 4 + 1) % 13).collect::<Vec<_>>();
+            ranks.sort_unstable_by(|a, b| b.cmp(a));
+            if chosen.len() > best.0 || (chosen.len() == best.0 && ranks < best.1) {
+                best = (chosen.len(), ranks);
+            }
+        }
+        let lowset = best.1.iter().fold(0, |acc, r| acc | (1 << r));
+        ((best.0 as u16) << 13) | (!lowset & 0x1fff)
+    }
+
+    #[test]
+    fn test_all_4card_combinations() {
+        let mut num_badugis = 0;
+        for i in 0..(NUMBER_OF_CARDS - 3) {
+            let hand = Hand::new().add_card(i);
+            for j in (i + 1)..(NUMBER_OF_CARDS - 2) {
+                let hand = hand.add_card(j);
+                for k in (j + 1)..(NUMBER_OF_CARDS - 1) {
+                    let hand = hand.add_card(k);
+                    for m in (k + 1)..NUMBER_OF_CARDS {
+                        let hand = hand.add_card(m);
+                        let rank = evaluate_badugi(&hand);
+                        assert_eq!(rank, evaluate_badugi_naive(&[i, j, k, m]));
+                        if get_badugi_size(rank) == 4 {
+                            num_badugis += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(num_badugis, 13 * 12 * 11 * 10);
+    }
+
+    #[test]
+    fn test_badugi_edge_cases() {
+        let badugi = |s: &str| evaluate_badugi(&s.parse::<Hand>().unwrap());
+        assert!(badugi("Ac2d3h4s") > badugi("Ac2d3h5s"));
+        assert!(badugi("Kc2d3hQs") > badugi("Ac2d3h3s"));
+        assert_eq!(badugi("Ac2d3h3s"), badugi("Ac2d3h"));
+        assert_eq!(badugi("Ac2c3c4c"), badugi("Ah"));
+        assert!(badugi("2c2d3h3s") < badugi("Ac2d"));
+        assert_eq!(
+            best_badugi_cards(&"Ac2d3h5h".parse().unwrap()),
+            "Ac2d3h".parse().unwrap()
+        );
+        assert_eq!(
+            best_badugi_cards(&"Ac2d3d8s".parse().unwrap()),
+            "Ac2d8s".parse().unwrap()
+        );
+    }
+
+    fn showdown(hand1: &Hand, hand2: &Hand) -> (f64, f64, f64) {
+        match evaluate_badugi(hand1).cmp(&evaluate_badugi(hand2)) {
+            std::cmp::Ordering::Greater => (1.0, 0.0, 0.0),
+            std::cmp::Ordering::Less => (0.0, 1.0, 0.0),
+            std::cmp::Ordering::Equal => (0.0, 0.0, 1.0),
+        }
+    }
+
+    fn assert_close(x: (f64, f64, f64), y: (f64, f64, f64)) {
+        assert!((x.0 - y.0).abs() < 1e-9, "{:?} vs {:?}", x, y);
+        assert!((x.1 - y.1).abs() < 1e-9, "{:?} vs {:?}", x, y);
+        assert!((x.2 - y.2).abs() < 1e-9, "{:?} vs {:?}", x, y);
+    }
+
+    /// Final hands after drawing at most one card to `kept` from the cards not in `used`.
+    fn one_card_draws(kept: &Hand, used_mask: u64) -> Vec<Hand> {
+        assert!(kept.len() >= 3);
+        match kept.len() {
+            4 => vec![*kept],
+            _ => (0..NUMBER_OF_CARDS)
+                .filter(|&c| (CARDS[c].1 & used_mask) == 0)
+                .map(|c| kept.add_card(c))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_draw_pat_badugi() {
+        // the best badugi stands pat and never loses
+        let hand1 = "Ac2d3h4s".parse().unwrap();
+        let hand2 = "5c6d7h".parse().unwrap();
+        let result = badugi_draw_win_frequency(&hand1, &hand2, &Hand::new(), 2);
+        assert_close(result, (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "1 alive cards cannot cover the draws, which need up to 2 cards")]
+    fn test_draw_too_few_cards() {
+        let hand1: Hand = "Ac2d3h".parse().unwrap();
+        let hand2: Hand = "4c5d6h".parse().unwrap();
+        // only the king of spades is left
+        let known = (hand1 + hand2).get_mask() | CARDS[47].1;
+        let dead_cards = Hand::from_slice(
+            &(0..NUMBER_OF_CARDS)
+                .filter(|&c| (CARDS[c].1 & known) == 0)
+                .collect::<Vec<_>>(),
+        );
+        badugi_draw_win_frequency(&hand1, &hand2, &dead_cards, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "num_draws must be 1 or 2, but got 3")]
+    fn test_draw_three_draws() {
+        let hand1 = "Ac2d3h".parse().unwrap();
+        let hand2 = "4c5d6h".parse().unwrap();
+        badugi_draw_win_frequency(&hand1, &hand2, &Hand::new(), 3);
+    }
+
+    #[test]
+    fn test_draw_one_draw() {
+        let hand1: Hand = "Ac2d3h".parse().unwrap();
+        let hand2: Hand = "4c5d6h".parse().unwrap();
+        let dead_cards: Hand = "Ks".parse().unwrap();
+        let result = badugi_draw_win_frequency(&hand1, &hand2, &dead_cards, 1);
+        let mut expected = (0.0, 0.0, 0.0);
+        let mut count = 0.0;
+        let known = (hand1 + hand2 + dead_cards).get_mask();
+        for final1 in one_card_draws(&hand1, known) {
+            for final2 in one_card_draws(&hand2, known | final1.get_mask()) {
+                let p = showdown(&final1, &final2);
+                expected = (expected.0 + p.0, expected.1 + p.1, expected.2 + p.2);
+                count += 1.0;
+            }
+        }
+        assert_close(
+            result,
+            (expected.0 / count, expected.1 / count, expected.2 / count),
+        );
+    }
+
+    #[test]
+    fn test_draw_two_draws() {
+        // weight each first-draw outcome equally, whatever the size of the second draw
+        // (a player who makes a badugi stands pat, and the other draws one card)
+        let hand1: Hand = "Ac2d3h".parse().unwrap();
+        let hand2: Hand = "2c4d5s".parse().unwrap();
+        let dead_cards = Hand::from_slice(&(24..48).collect::<Vec<_>>());
+        let result = badugi_draw_win_frequency(&hand1, &hand2, &dead_cards, 2);
+
+        let known = (hand1 + hand2 + dead_cards).get_mask();
+        let mut expected = (0.0, 0.0, 0.0);
+        let mut count = 0.0;
+        let (mut leaf_wins, mut num_leaves) = (0.0, 0.0);
+        for first1 in one_card_draws(&hand1, known) {
+            for first2 in one_card_draws(&hand2, known | first1.get_mask()) {
+                let (kept1, kept2) = (best_badugi_cards(&first1), best_badugi_cards(&first2));
+                let used = known | first1.get_mask() | first2.get_mask();
+                let mut sub = (0.0, 0.0, 0.0);
+                let mut sub_count = 0.0;
+                for final1 in one_card_draws(&kept1, used) {
+                    for final2 in one_card_draws(&kept2, used | final1.get_mask()) {
+                        let p = showdown(&final1, &final2);
+                        sub = (sub.0 + p.0, sub.1 + p.1, sub.2 + p.2);
+                        sub_count += 1.0;
+                    }
+                }
+                leaf_wins += sub.0;
+                num_leaves += sub_count;
+                expected.0 += sub.0 / sub_count;
+                expected.1 += sub.1 / sub_count;
+                expected.2 += sub.2 / sub_count;
+                count += 1.0;
+            }
+        }
+        let expected = (expected.0 / count, expected.1 / count, expected.2 / count);
+        assert_close(result, expected);
+        assert!((expected.0 + expected.1 + expected.2 - 1.0).abs() < 1e-9);
+
+        // the subtrees differ in size, so unweighted leaf counts are not probabilities
+        assert!((leaf_wins / num_leaves - expected.0).abs() > 1e-3);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::hand::*;
+use crate::tables::{LOOKUP, LOOKUP_FLUSH, OFFSETS};
+use assets::constants::*;
+
+/// Evaluates `hands` and writes the hand strengths to `out` (same result as `Hand::evaluate()`).
+/// Every element of `hands` must consist of 5-7 cards.
+#[inline]
+pub fn evaluate_batch(hands: &[Hand], out: &mut [u16]) {
+    assert_eq!(hands.len(), out.len());
+    for (hand, out) in hands.iter().zip(out.iter_mut()) {
+        *out = evaluate_branchless(hand.get_key(), hand.get_mask());
+    }
+}
+
+/// Struct-of-arrays version of `evaluate_batch()`: `keys[i]` and `masks[i]` are the values of
+/// `Hand::get_key()` and `Hand::get_mask()` of the `i`-th hand.
+#[inline]
+pub fn evaluate_batch_soa(keys: &[u64], masks: &[u64], out: &mut [u16]) {
+    assert_eq!(keys.len(), out.len());
+    assert_eq!(masks.len(), out.len());
+    for ((key, mask), out) in keys.iter().zip(masks).zip(out.iter_mut()) {
+        *out = evaluate_branchless(*key, *mask);
+    }
+}
+
+/// Branchless version of `Hand::evaluate()`: looks up both tables and selects the result.
+/// Both indices are always in range for 5-7 card hands, so the loop can become two gathers
+/// and a blend.
+#[inline(always)]
+fn evaluate_branchless(key: u64, mask: u64) -> u16 {
+    let is_flush = key & FLUSH_MASK;
+    // the shift amount is 0, 16, 32 or 48 for flushes and 0 (wrapped from 256) otherwise
+    let flush_key = (mask >> ((4 * is_flush.leading_zeros()) & 63)) as u16 as usize;
+    let rank_key = key as u32 as usize;
+    let offset = unsafe { *OFFSETS.get_unchecked(rank_key >> OFFSET_SHIFT) as usize };
+    let hash_key = rank_key.wrapping_add(offset);
+    let flush_rank = unsafe { *LOOKUP_FLUSH.get_unchecked(flush_key & 0x1fff) };
+    let rank = unsafe { *LOOKUP.get_unchecked(hash_key) };
+    if is_flush > 0 {
+        flush_rank
+    } else {
+        rank
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_5card_combinations() {
+        let mut hands = Vec::new();
+        for i in 0..(NUMBER_OF_CARDS - 4) {
+            let hand = Hand::new().add_card(i);
+            for j in (i + 1)..(NUMBER_OF_CARDS - 3) {
+                let hand = hand.add_card(j);
+                for k in (j + 1)..(NUMBER_OF_CARDS - 2) {
+                    let hand = hand.add_card(k);
+                    for m in (k + 1)..(NUMBER_OF_CARDS - 1) {
+                        let hand = hand.add_card(m);
+                        for n in (m + 1)..NUMBER_OF_CARDS {
+                            hands.push(hand.add_card(n));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut out = vec![0; hands.len()];
+        evaluate_batch(&hands, &mut out);
+        assert!(hands.iter().zip(&out).all(|(h, r)| h.evaluate() == *r));
+
+        let keys = hands.iter().map(|h| h.get_key()).collect::<Vec<_>>();
+        let masks = hands.iter().map(|h| h.get_mask()).collect::<Vec<_>>();
+        let mut out = vec![0; hands.len()];
+        evaluate_batch_soa(&keys, &masks, &mut out);
+        assert!(hands.iter().zip(&out).all(|(h, r)| h.evaluate() == *r));
+    }
+
+    #[test]
+    fn test_random_7card_hands() {
+        let mut rng = crate::rng::Rng::new(0);
+        let mut hands = Vec::new();
+        for _ in 0..1000000 {
+            let mut hand = Hand::new();
+            while hand.len() < 7 {
+                let card = rng.gen_below(NUMBER_OF_CARDS);
+                if !hand.contains(card) {
+                    hand = hand.add_card(card);
+                }
+            }
+            hands.push(hand);
+        }
+        let mut out = vec![0; hands.len()];
+        evaluate_batch(&hands, &mut out);
+        assert!(hands.iter().zip(&out).all(|(h, r)| h.evaluate() == *r));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::hand::*;
+use crate::tables::{LOOKUP, LOOKUP_FLUSH, OFFSETS};
+use assets::constants::*;
+
+/// Evaluates many boards against a fixed set of two-card hole hands.
+/// The keys of the hole hands are precomputed, and flush checks are skipped unless some suit
+/// has 3 or more cards on the board.
+#[derive(Clone, Debug)]
+pub struct BoardEvaluator {
+    holes: Vec<(u64, u64)>,
+}
+
+impl BoardEvaluator {
+    /// Creates a new evaluator. `hole_hands` must consist of 2 or more non-overlapping
+    /// two-card hands.
+    pub fn new(hole_hands: &[Hand]) -> Self {
+        assert!(hole_hands.len() >= 2);
+        assert!(hole_hands.iter().all(|h| h.len() == 2));
+        let combined = hole_hands.iter().fold(Hand::new(), |acc, h| acc + *h);
+        assert_eq!(combined.len(), 2 * hole_hands.len());
+        Self {
+            // subtract the initial suit counters so that the keys can be added to board keys
+            holes: hole_hands
+                .iter()
+                .map(|h| (h.get_key().wrapping_sub(0x3333 << SUIT_SHIFT), h.get_mask()))
+                .collect(),
+        }
+    }
+
+    /// Returns the number of hole hands.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.holes.len()
+    }
+
+    /// Checks whether the evaluator has no hole hands (always `false`).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.holes.is_empty()
+    }
+
+    /// Writes the hand strength of each hole hand combined with `board` (3-5 cards) to `ranks`.
+    /// The result is the same as `(hole_hand + board).evaluate()`; `board` must not contain any
+    /// card of the hole hands.
+    #[inline]
+    pub fn evaluate(&self, board: &Hand, ranks: &mut [u16]) {
+        debug_assert!(3 <= board.len() && board.len() <= 5);
+        debug_assert!(self.holes.iter().all(|h| (h.1 & board.get_mask()) == 0));
+        assert_eq!(ranks.len(), self.len());
+        let board_key = board.get_key();
+
+        // two hole cards can complete a flush only when the board has 3 or more cards of a suit
+        let can_flush = board_key.wrapping_add(0x2222 << SUIT_SHIFT) & FLUSH_MASK;
+        if can_flush == 0 {
+            for (rank, (key, _)) in ranks.iter_mut().zip(&self.holes) {
+                *rank = lookup_rank(board_key.wrapping_add(*key) as u32 as usize);
+            }
+        } else {
+            let shift = 4 * can_flush.leading_zeros();
+            let board_mask = board.get_mask();
+            for (rank, (key, mask)) in ranks.iter_mut().zip(&self.holes) {
+                let key = board_key.wrapping_add(*key);
+                *rank = if (key & FLUSH_MASK) != 0 {
+                    let flush_key = ((board_mask | mask) >> shift) as u16;
+                    unsafe { *LOOKUP_FLUSH.get_unchecked(flush_key as usize) }
+                } else {
+                    lookup_rank(key as u32 as usize)
+                };
+            }
+        }
+    }
+}
+
+#[inline]
+fn lookup_rank(rank_key: usize) -> u16 {
+    let offset = unsafe { *OFFSETS.get_unchecked(rank_key >> OFFSET_SHIFT) as usize };
+    let hash_key = rank_key.wrapping_add(offset);
+    unsafe { *LOOKUP.get_unchecked(hash_key) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heads_up::*;
+    use crate::rng::Rng;
+
+    fn draw(rng: &mut Rng, used: &mut Hand, num_cards: usize) -> Hand {
+        let mut hand = Hand::new();
+        while hand.len() < num_cards {
+            let card = rng.gen_below(NUMBER_OF_CARDS);
+            if !used.contains(card) {
+                *used = used.add_card(card);
+                hand = hand.add_card(card);
+            }
+        }
+        hand
+    }
+
+    #[test]
+    fn test_random_boards() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            let num_players = 2 + rng.gen_below(8);
+            let num_board = 3 + rng.gen_below(3);
+            let mut used = Hand::new();
+            let hole_hands = (0..num_players)
+                .map(|_| draw(&mut rng, &mut used, 2))
+                .collect::<Vec<_>>();
+            let evaluator = BoardEvaluator::new(&hole_hands);
+            for _ in 0..100 {
+                let mut board_used = used;
+                let board = draw(&mut rng, &mut board_used, num_board);
+                let mut ranks = vec![0; num_players];
+                evaluator.evaluate(&board, &mut ranks);
+                for (hole, rank) in hole_hands.iter().zip(&ranks) {
+                    assert_eq!(*rank, (*hole + board).evaluate());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_heads_up_preflop() {
+        let hand1 = "AsKs".parse::<Hand>().unwrap();
+        let hand2 = "QhQd".parse::<Hand>().unwrap();
+        let evaluator = BoardEvaluator::new(&[hand1, hand2]);
+        let alive_cards = (0..NUMBER_OF_CARDS)
+            .filter(|&c| !(hand1 + hand2).contains(c))
+            .collect::<Vec<_>>();
+        let len = alive_cards.len();
+        let mut result = (0, 0, 0);
+        let mut ranks = [0; 2];
+        for i in 0..(len - 4) {
+            let board = Hand::new().add_card(alive_cards[i]);
+            for j in (i + 1)..(len - 3) {
+                let board = board.add_card(alive_cards[j]);
+                for k in (j + 1)..(len - 2) {
+                    let board = board.add_card(alive_cards[k]);
+                    for m in (k + 1)..(len - 1) {
+                        let board = board.add_card(alive_cards[m]);
+                        for n in (m + 1)..len {
+                            let board = board.add_card(alive_cards[n]);
+                            evaluator.evaluate(&board, &mut ranks);
+                            match ranks[0].cmp(&ranks[1]) {
+                                std::cmp::Ordering::Greater => result.0 += 1,
+                                std::cmp::Ordering::Less => result.1 += 1,
+                                std::cmp::Ordering::Equal => result.2 += 1,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let expected = heads_up_win_frequency(&hand1, &hand2, &Hand::new(), &Hand::new());
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::hand::*;
+use crate::rng::Rng;
+use assets::constants::*;
+
+/// Deck of cards that deals from the top.
+/// A new deck is ordered by card ID; call `shuffle()` before dealing.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Deck {
+    // remaining cards are `cards[..len]`, and the top card is `cards[len - 1]`
+    cards: [u8; NUMBER_OF_CARDS],
+    len: usize,
+    burned: Hand,
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deck {
+    /// Creates a full 52-card deck.
+    #[inline]
+    pub const fn new() -> Self {
+        let mut cards = [0; NUMBER_OF_CARDS];
+        let mut i = 0;
+        while i < NUMBER_OF_CARDS {
+            cards[i] = i as u8;
+            i += 1;
+        }
+        Self {
+            cards,
+            len: NUMBER_OF_CARDS,
+            burned: Hand::new(),
+        }
+    }
+
+    /// Creates a deck without `known_cards` (e.g., hole cards and the board already known).
+    #[inline]
+    pub fn without(known_cards: &Hand) -> Self {
+        let mut deck = Self::new();
+        deck.remove(known_cards);
+        deck
+    }
+
+    /// Returns the number of remaining cards.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether no card remains.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the remaining cards.
+    #[inline]
+    pub fn remaining(&self) -> Hand {
+        self.cards[..self.len]
+            .iter()
+            .fold(Hand::new(), |hand, &card| hand.add_card(card as usize))
+    }
+
+    /// Returns the cards burned so far.
+    #[inline]
+    pub const fn burned(&self) -> Hand {
+        self.burned
+    }
+
+    /// Removes `cards` from the remaining cards, keeping the order of the others.
+    /// All of `cards` must remain in the deck.
+    pub fn remove(&mut self, cards: &Hand) {
+        let mut len = 0;
+        for i in 0..self.len {
+            let card = self.cards[i];
+            if !cards.contains(card as usize) {
+                self.cards[len] = card;
+                len += 1;
+            }
+        }
+        assert_eq!(self.len - len, cards.len());
+        self.len = len;
+    }
+
+    /// Shuffles the remaining cards uniformly at random (Fisher-Yates shuffle).
+    #[inline]
+    pub fn shuffle(&mut self, rng: &mut Rng) {
+        self.shuffle_top(self.len.saturating_sub(1), rng);
+    }
+
+    /// Shuffles only the top `n` cards: they become a uniformly random sample of the remaining
+    /// cards in random order (partial Fisher-Yates shuffle), which is enough for dealing `n`
+    /// cards and cheaper than `shuffle()`. Panics if fewer than `n` cards remain.
+    pub fn shuffle_top(&mut self, n: usize, rng: &mut Rng) {
+        assert!(n <= self.len);
+        for i in ((self.len - n)..self.len).rev() {
+            let j = rng.gen_below(i + 1);
+            self.cards.swap(i, j);
+        }
+    }
+
+    /// Deals the top card. Panics if the deck is empty.
+    #[inline]
+    pub fn deal_card(&mut self) -> usize {
+        assert!(self.len > 0);
+        self.len -= 1;
+        self.cards[self.len] as usize
+    }
+
+    /// Deals the top `n` cards. Panics if fewer than `n` cards remain.
+    #[inline]
+    pub fn deal(&mut self, n: usize) -> Hand {
+        assert!(n <= self.len);
+        let mut hand = Hand::new();
+        for _ in 0..n {
+            hand = hand.add_card(self.deal_card());
+        }
+        hand
+    }
+
+    /// Discards the top card face down. Panics if the deck is empty.
+    #[inline]
+    pub fn burn(&mut self) {
+        let card = self.deal_card();
+        self.burned = self.burned.add_card(card);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::heads_up::*;
+
+    #[test]
+    fn test_deal_and_burn() {
+        let known = "AsKsQhQd".parse::<Hand>().unwrap();
+        let mut deck = Deck::without(&known);
+        assert_eq!(deck.len(), 48);
+        assert_eq!(
+            deck.remaining() + known,
+            Hand::from_slice(&(0..52).collect::<Vec<_>>())
+        );
+
+        deck.shuffle(&mut Rng::new(0));
+        assert_eq!(deck.remaining().len(), 48);
+        deck.burn();
+        let flop = deck.deal(3);
+        deck.burn();
+        let turn = deck.deal(1);
+        deck.burn();
+        let river = deck.deal(1);
+        assert_eq!(deck.len(), 40);
+        assert_eq!(deck.burned().len(), 3);
+        let all = known + deck.remaining() + deck.burned() + flop + turn + river;
+        assert_eq!(all.len(), 52);
+
+        let mut deck = Deck::new();
+        let hand = deck.deal(52);
+        assert_eq!(hand.len(), 52);
+        assert!(deck.is_empty());
+        assert_eq!(deck.remaining(), Hand::new());
+    }
+
+    #[test]
+    fn test_new_deck_order() {
+        let mut deck = Deck::default();
+        assert_eq!(deck.deal_card(), 51);
+        deck.remove(&Hand::from_slice(&[50, 0]));
+        assert_eq!(deck.deal_card(), 49);
+        assert_eq!(deck.len(), 48);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remove_missing_card() {
+        let mut deck = Deck::new();
+        let card = deck.deal(1);
+        deck.remove(&card);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_deal_too_many() {
+        let mut deck = Deck::without(&Hand::from_slice(&(0..50).collect::<Vec<_>>()));
+        deck.deal(3);
+    }
+
+    #[test]
+    fn test_seed() {
+        let shuffled = |seed| {
+            let mut deck = Deck::new();
+            deck.shuffle(&mut Rng::new(seed));
+            (0..52).map(|_| deck.deal_card()).collect::<Vec<_>>()
+        };
+        assert_eq!(shuffled(1), shuffled(1));
+        assert_ne!(shuffled(1), shuffled(2));
+    }
+
+    #[test]
+    fn test_uniform_positions() {
+        // chi-squared test of the frequency of each card at each position
+        let num_trials = 100000;
+        let mut rng = Rng::new(0);
+        let mut count = vec![[0u32; NUMBER_OF_CARDS]; NUMBER_OF_CARDS];
+        for _ in 0..num_trials {
+            let mut deck = Deck::new();
+            deck.shuffle(&mut rng);
+            for position_count in count.iter_mut() {
+                position_count[deck.deal_card()] += 1;
+            }
+        }
+        let expected = num_trials as f64 / NUMBER_OF_CARDS as f64;
+        let chi2 = count
+            .iter()
+            .flatten()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        // 51 * 51 = 2601 degrees of freedom (standard deviation ~72)
+        assert!((2601.0 - 500.0..2601.0 + 500.0).contains(&chi2), "{}", chi2);
+    }
+
+    #[test]
+    fn test_uniform_permutations() {
+        // all 24 orders of a 4-card deck should appear equally often
+        let num_trials = 240000;
+        let mut rng = Rng::new(1);
+        let base = Deck::without(&Hand::from_slice(&(4..52).collect::<Vec<_>>()));
+        let mut count = [0u32; 256];
+        for _ in 0..num_trials {
+            let mut deck = base;
+            deck.shuffle(&mut rng);
+            let order = (0..4).fold(0, |acc, _| acc * 4 + deck.deal_card());
+            count[order] += 1;
+        }
+        let observed = count.iter().filter(|&&c| c > 0).collect::<Vec<_>>();
+        assert_eq!(observed.len(), 24);
+        let expected = num_trials as f64 / 24.0;
+        let chi2 = observed
+            .iter()
+            .map(|&&c| (c as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        // 23 degrees of freedom: P(chi2 > 60) < 1e-4
+        assert!(chi2 < 60.0, "{}", chi2);
+    }
+
+    #[test]
+    fn test_uniform_shuffle_top() {
+        // all 12 ordered pairs of a 4-card deck should be dealt equally often
+        let num_trials = 120000;
+        let mut rng = Rng::new(3);
+        let base = Deck::without(&Hand::from_slice(&(4..52).collect::<Vec<_>>()));
+        let mut count = [0u32; 16];
+        for _ in 0..num_trials {
+            let mut deck = base;
+            deck.shuffle_top(2, &mut rng);
+            count[deck.deal_card() * 4 + deck.deal_card()] += 1;
+        }
+        let observed = count.iter().filter(|&&c| c > 0).collect::<Vec<_>>();
+        assert_eq!(observed.len(), 12);
+        let expected = num_trials as f64 / 12.0;
+        let chi2 = observed
+            .iter()
+            .map(|&&c| (c as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        // 11 degrees of freedom: P(chi2 > 40) < 1e-4
+        assert!(chi2 < 40.0, "{}", chi2);
+
+        let mut deck = base;
+        deck.shuffle_top(0, &mut rng);
+        assert_eq!(deck, base);
+    }
+
+    #[test]
+    fn test_monte_carlo_equity() {
+        let hand1 = "AsKs".parse::<Hand>().unwrap();
+        let hand2 = "QhQd".parse::<Hand>().unwrap();
+        let flop = "2s7sTc".parse::<Hand>().unwrap();
+        let (win1, win2, tie) = heads_up_win_frequency(&hand1, &hand2, &flop, &Hand::new());
+        let exact = (win1 as f64 + tie as f64 / 2.0) / (win1 + win2 + tie) as f64;
+
+        let num_trials = 100000;
+        let mut rng = Rng::new(2);
+        let base = Deck::without(&(hand1 + hand2 + flop));
+        let mut equity = 0.0;
+        for _ in 0..num_trials {
+            let mut deck = base;
+            deck.shuffle_top(2, &mut rng);
+            let board = flop + deck.deal(2);
+            let rank1 = (hand1 + board).evaluate();
+            let rank2 = (hand2 + board).evaluate();
+            equity += match rank1.cmp(&rank2) {
+                core::cmp::Ordering::Greater => 1.0,
+                core::cmp::Ordering::Less => 0.0,
+                core::cmp::Ordering::Equal => 0.5,
+            };
+        }
+        assert!((equity / num_trials as f64 - exact).abs() < 0.01);
+    }
+}
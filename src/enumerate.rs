@@ -1,33 +1,42 @@
+use crate::error::*;
 use crate::hand::*;
-use assets::constants::*;
 
 const NUM_HAND_CATEGORIES: usize = HandCategory::StraightFlush as usize + 1;
 
 /// Enumerates possible hand categories from `hand`.
+///
+/// Panics on invalid input; see `try_enumerate_hand_category()`.
 pub fn enumerate_hand_category(hand: &Hand, dead_cards: &Hand) -> [u32; NUM_HAND_CATEGORIES] {
-    assert!(2 <= hand.len() && hand.len() <= 7);
-    assert!((hand.get_mask() & dead_cards.get_mask()) == 0);
-    let alive_cards = compute_alive_cards(hand.get_mask() | dead_cards.get_mask());
-    assert!(alive_cards.len() >= 7 - hand.len());
-    match hand.len() {
-        2 => enumerate_hand_category_2(hand, &alive_cards),
-        3 => enumerate_hand_category_3(hand, &alive_cards),
-        4 => enumerate_hand_category_4(hand, &alive_cards),
-        5 => enumerate_hand_category_5(hand, &alive_cards),
-        6 => enumerate_hand_category_6(hand, &alive_cards),
-        7 => enumerate_hand_category_7(hand, &alive_cards),
-        _ => unreachable!(),
-    }
+    try_enumerate_hand_category(hand, dead_cards).unwrap_or_else(|e| panic!("{}", e))
 }
 
-fn compute_alive_cards(mask: u64) -> Vec<usize> {
-    let mut result = Vec::new();
-    for i in 0..NUMBER_OF_CARDS {
-        if (CARDS[i].1 & mask) == 0 {
-            result.push(i);
-        }
+/// Enumerates possible hand categories from `hand`, or returns an error when `hand` does not
+/// consist of 2-7 cards, `hand` and `dead_cards` share a card, or not enough cards remain.
+pub fn try_enumerate_hand_category(
+    hand: &Hand,
+    dead_cards: &Hand,
+) -> Result<[u32; NUM_HAND_CATEGORIES], EquityError> {
+    if hand.len() < 2 || hand.len() > 7 {
+        return Err(EquityError::InvalidHandSize(hand.len()));
     }
-    result
+    if (hand.get_mask() & dead_cards.get_mask()) != 0 {
+        return Err(EquityError::OverlappingCards);
+    }
+    let (alive_cards, num_alive_cards) =
+        compute_alive_cards(hand.get_mask() | dead_cards.get_mask());
+    let alive_cards = &alive_cards[..num_alive_cards];
+    if alive_cards.len() < 7 - hand.len() {
+        return Err(EquityError::NotEnoughLiveCards);
+    }
+    Ok(match hand.len() {
+        2 => enumerate_hand_category_2(hand, alive_cards),
+        3 => enumerate_hand_category_3(hand, alive_cards),
+        4 => enumerate_hand_category_4(hand, alive_cards),
+        5 => enumerate_hand_category_5(hand, alive_cards),
+        6 => enumerate_hand_category_6(hand, alive_cards),
+        7 => enumerate_hand_category_7(hand, alive_cards),
+        _ => unreachable!(),
+    })
 }
 
 fn enumerate_hand_category_2(hand: &Hand, alive_cards: &[usize]) -> [u32; NUM_HAND_CATEGORIES] {
@@ -0,0 +1,145 @@
+use core::fmt;
+
+/// Error returned by `try_heads_up_win_frequency()`, `try_enumerate_hand_category()` and
+/// `OfcBoard::try_new()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EquityError {
+    /// A player holds a wrong number of hole cards (the actual number).
+    InvalidHoleCards(usize),
+    /// The board does not consist of 0, 3, 4 or 5 cards (the actual number).
+    InvalidBoardSize(usize),
+    /// The hand to enumerate does not consist of 2-7 cards (the actual number).
+    InvalidHandSize(usize),
+    /// A row of an OFC board has a wrong number of cards (the actual number).
+    InvalidRowSize(usize),
+    /// The same card appears more than once.
+    OverlappingCards,
+    /// Not enough cards remain to deal the rest of the hand.
+    NotEnoughLiveCards,
+}
+
+impl fmt::Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EquityError::InvalidHoleCards(n) => write!(f, "invalid number of hole cards: {}", n),
+            EquityError::InvalidBoardSize(n) => {
+                write!(f, "board must consist of 0, 3, 4 or 5 cards, but got {}", n)
+            }
+            EquityError::InvalidHandSize(n) => {
+                write!(f, "hand must consist of 2-7 cards, but got {}", n)
+            }
+            EquityError::InvalidRowSize(n) => {
+                write!(f, "invalid number of cards in an OFC row: {}", n)
+            }
+            EquityError::OverlappingCards => write!(f, "the same card is used more than once"),
+            EquityError::NotEnoughLiveCards => write!(f, "not enough live cards"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EquityError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumerate::*;
+    use crate::hand::*;
+    use crate::heads_up::*;
+
+    #[test]
+    fn test_heads_up_errors() {
+        let aa = Hand::from_slice(&[51, 50]);
+        let kk = Hand::from_slice(&[47, 46]);
+        let flop = Hand::from_slice(&[0, 5, 10]);
+        let empty = Hand::new();
+        let try_freq = |h1: &Hand, h2: &Hand, board: &Hand, dead: &Hand| {
+            try_heads_up_win_frequency(h1, h2, board, dead)
+        };
+        assert_eq!(
+            try_freq(&aa, &kk, &flop, &empty),
+            Ok(heads_up_win_frequency(&aa, &kk, &flop, &empty))
+        );
+        assert_eq!(
+            try_freq(&aa.add_card(0), &kk, &empty, &empty),
+            Err(EquityError::InvalidHoleCards(3))
+        );
+        assert_eq!(
+            try_freq(&aa, &kk.add_card(0), &empty, &empty),
+            Err(EquityError::InvalidHoleCards(3))
+        );
+        assert_eq!(
+            try_freq(&aa, &kk, &flop.remove_card(0), &empty),
+            Err(EquityError::InvalidBoardSize(2))
+        );
+        assert_eq!(
+            try_freq(&aa, &aa, &flop, &empty),
+            Err(EquityError::OverlappingCards)
+        );
+        assert_eq!(
+            try_freq(&aa, &kk, &flop, &flop),
+            Err(EquityError::OverlappingCards)
+        );
+
+        // 52 - 2 - 3 - 44 = 3 cards remain, but 2 + 2 cards are required
+        let dead = (4..47)
+            .filter(|c| !flop.contains(*c))
+            .fold(Hand::new(), |dead, c| dead.add_card(c));
+        let dead = dead.add_card(1).add_card(2).add_card(3);
+        assert_eq!(dead.len(), 44);
+        assert_eq!(
+            try_freq(&aa, &empty, &flop, &dead),
+            Err(EquityError::NotEnoughLiveCards)
+        );
+        assert!(try_freq(&aa, &empty, &flop, &dead.remove_card(3)).is_ok());
+    }
+
+    #[test]
+    fn test_enumerate_errors() {
+        let hand = Hand::from_slice(&[51, 50, 0, 5, 10]);
+        let empty = Hand::new();
+        assert_eq!(
+            try_enumerate_hand_category(&hand, &empty),
+            Ok(enumerate_hand_category(&hand, &empty))
+        );
+        assert_eq!(
+            try_enumerate_hand_category(&Hand::from_slice(&[0]), &empty),
+            Err(EquityError::InvalidHandSize(1))
+        );
+        assert_eq!(
+            try_enumerate_hand_category(&Hand::from_slice(&[0, 1, 2, 3, 4, 5, 6, 7]), &empty),
+            Err(EquityError::InvalidHandSize(8))
+        );
+        assert_eq!(
+            try_enumerate_hand_category(&hand, &Hand::from_slice(&[0])),
+            Err(EquityError::OverlappingCards)
+        );
+        let dead = (1..50)
+            .filter(|c| !hand.contains(*c))
+            .fold(Hand::new(), |dead, c| dead.add_card(c));
+        assert_eq!(
+            try_enumerate_hand_category(&hand, &dead),
+            Err(EquityError::NotEnoughLiveCards)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "board must consist of 0, 3, 4 or 5 cards, but got 1")]
+    fn test_heads_up_panic() {
+        let hand1 = Hand::from_slice(&[51, 50]);
+        let hand2 = Hand::from_slice(&[47, 46]);
+        heads_up_win_frequency(&hand1, &hand2, &Hand::from_slice(&[0]), &Hand::new());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_display() {
+        let error = EquityError::InvalidHandSize(8);
+        assert_eq!(
+            error.to_string(),
+            "hand must consist of 2-7 cards, but got 8"
+        );
+        let error: Box<dyn std::error::Error> = Box::new(EquityError::OverlappingCards);
+        assert_eq!(error.to_string(), "the same card is used more than once");
+    }
+}
@@ -1,10 +1,13 @@
+use crate::tables::{LOOKUP, LOOKUP_FLUSH, OFFSETS};
 use assets::constants::*;
-use assets::lookup::{LOOKUP, LOOKUP_FLUSH};
-use assets::offsets::OFFSETS;
-use std::ops::{Add, AddAssign};
-use std::str::FromStr;
+use core::ops::{Add, AddAssign};
+#[cfg(feature = "std")]
+use core::str::FromStr;
 
+/// Category of a hand strength (see `get_hand_category()`).
+/// The enum is `#[non_exhaustive]` so that categories can be added without a breaking release.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
 pub enum HandCategory {
     HighCard = 0,
     OnePair = 1,
@@ -15,11 +18,13 @@ pub enum HandCategory {
     FullHouse = 6,
     FourOfAKind = 7,
     StraightFlush = 8,
+    /// Only produced by `evaluate_with_wilds()`.
+    FiveOfAKind = 9,
 }
 
 /// Returns the hand category from hand rank computed by `Hand::evaluate()`.
 #[inline]
-pub fn get_hand_category(hand_rank: u16) -> HandCategory {
+pub const fn get_hand_category(hand_rank: u16) -> HandCategory {
     match hand_rank >> 12 {
         0 => HandCategory::HighCard,
         1 => HandCategory::OnePair,
@@ -30,20 +35,42 @@ pub fn get_hand_category(hand_rank: u16) -> HandCategory {
         6 => HandCategory::FullHouse,
         7 => HandCategory::FourOfAKind,
         8 => HandCategory::StraightFlush,
+        9 => HandCategory::FiveOfAKind,
         _ => unreachable!(),
     }
 }
 
+/// Returns the rank of the pair (0 for deuces, 12 for aces) from hand rank computed by
+/// `Hand::evaluate()`, or `None` when the hand category is not `OnePair`.
+#[inline]
+pub const fn get_pair_rank(hand_rank: u16) -> Option<usize> {
+    match get_hand_category(hand_rank) {
+        // 220 (= 12 choose 3) kicker combinations for each pair
+        HandCategory::OnePair => Some((hand_rank & 0xfff) as usize / 220),
+        _ => None,
+    }
+}
+
+// `repr(C)` so that `Hand` can be passed by value through the C ABI (see `ffi/src/lib.rs`)
+#[repr(C)]
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Hand {
     key: u64,
     mask: u64,
 }
 
+/// Returns `CARDS[card]` without bounds checking; `card` must be in the range \[0, 51\].
+#[inline]
+const fn card_entry(card: usize) -> (u64, u64) {
+    debug_assert!(card < NUMBER_OF_CARDS, "card must be in the range [0, 51]");
+    // raw pointer read for the same reason as in `evaluate_with()`
+    unsafe { *CARDS.as_ptr().add(card) }
+}
+
 impl Hand {
     /// Creates an empty `Hand` struct.
     #[inline]
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
             key: 0x3333 << SUIT_SHIFT,
             mask: 0,
@@ -54,44 +81,54 @@ impl Hand {
     /// Elements in `cards` must be in the range \[0, 51\].
     /// (0 corresponds to the deuce of clubs, and 51 corresponds to the ace of spades)
     #[inline]
-    pub fn from_slice(cards: &[usize]) -> Self {
+    pub const fn from_slice(cards: &[usize]) -> Self {
         let mut hand = Self::new();
-        for card in cards {
-            hand = hand.add_card(*card);
+        let mut i = 0;
+        while i < cards.len() {
+            hand = hand.add_card(cards[i]);
+            i += 1;
         }
         hand
     }
 
     /// Checks whether the hand is empty.
     #[inline]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.mask == 0
     }
 
     /// Returns current number of cards in `self`.
     #[inline]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.mask.count_ones() as usize
     }
 
+    /// Returns the key of `self` (rank key in the lower 32 bits and suit counters in the upper bits).
+    #[inline]
+    pub const fn get_key(&self) -> u64 {
+        self.key
+    }
+
     /// Returns the bit mask of `self`.
     #[inline]
-    pub fn get_mask(&self) -> u64 {
+    pub const fn get_mask(&self) -> u64 {
         self.mask
     }
 
     /// Returns whether the `card` is included in `self`.
+    /// `card` must be in the range \[0, 51\] (this is only checked in debug builds).
     #[inline]
-    pub fn contains(&self, card: usize) -> bool {
-        (self.mask & unsafe { *CARDS.get_unchecked(card) }.1) != 0
+    pub const fn contains(&self, card: usize) -> bool {
+        (self.mask & card_entry(card).1) != 0
     }
 
     /// Returns a new hand struct where `card` is added to `self`.
     /// `card` must be in the range \[0, 51\] and must not be already included in `self`.
     /// (0 corresponds to the deuce of clubs, and 51 corresponds to the ace of spades)
+    /// The range is only checked in debug builds.
     #[inline]
-    pub fn add_card(&self, card: usize) -> Self {
-        let (k, m) = unsafe { *CARDS.get_unchecked(card) };
+    pub const fn add_card(&self, card: usize) -> Self {
+        let (k, m) = card_entry(card);
         Self {
             key: self.key.wrapping_add(k),
             mask: self.mask.wrapping_add(m),
@@ -100,9 +137,10 @@ impl Hand {
 
     /// Returns a new hand struct where `card` is removed from `self`.
     /// `card` must be in the range \[0, 51\] and included in `self`.
+    /// The range is only checked in debug builds.
     #[inline]
-    pub fn remove_card(&self, card: usize) -> Self {
-        let (k, m) = unsafe { *CARDS.get_unchecked(card) };
+    pub const fn remove_card(&self, card: usize) -> Self {
+        let (k, m) = card_entry(card);
         Self {
             key: self.key.wrapping_sub(k),
             mask: self.mask.wrapping_sub(m),
@@ -110,24 +148,198 @@ impl Hand {
     }
 
     /// Returns hand strength in 16-bit integer.
-    /// This function may crush when `self.len() < 5 || self.len() > 7`.
+    /// This function may crush when `self.len() < 5 || self.len() > 7`
+    /// (use `evaluate_n()` for 8- and 9-card hands).
+    /// In debug builds, this function panics when `self.len()` is out of the range \[5, 7\].
+    /// This is a `const fn` unless the `runtime-tables` feature is enabled.
+    #[cfg(not(feature = "runtime-tables"))]
+    #[inline]
+    pub const fn evaluate(&self) -> u16 {
+        self.evaluate_with(&LOOKUP, &LOOKUP_FLUSH, &OFFSETS)
+    }
+
+    /// Returns hand strength in 16-bit integer.
+    /// This function may crush when `self.len() < 5 || self.len() > 7`
+    /// (use `evaluate_n()` for 8- and 9-card hands).
+    /// In debug builds, this function panics when `self.len()` is out of the range \[5, 7\].
+    #[cfg(feature = "runtime-tables")]
     #[inline]
     pub fn evaluate(&self) -> u16 {
+        self.evaluate_with(&LOOKUP, &LOOKUP_FLUSH, &OFFSETS)
+    }
+
+    #[inline]
+    const fn evaluate_with(&self, lookup: &[u16], lookup_flush: &[u16], offsets: &[i32]) -> u16 {
+        let len = self.len();
+        debug_assert!(5 <= len && len <= 7, "evaluate() requires 5-7 cards");
+        // raw pointer reads avoid bounds checks and, unlike `get_unchecked()`, are allowed in
+        // const contexts (out-of-bounds reads are compile errors in const evaluation)
         let is_flush = self.key & FLUSH_MASK;
         if is_flush > 0 {
             let flush_key = (self.mask >> (4 * is_flush.leading_zeros())) as u16;
-            unsafe { *LOOKUP_FLUSH.get_unchecked(flush_key as usize) }
+            unsafe { *lookup_flush.as_ptr().add(flush_key as usize) }
         } else {
             let rank_key = self.key as u32 as usize;
-            let offset = unsafe { *OFFSETS.get_unchecked(rank_key >> OFFSET_SHIFT) as usize };
+            let offset = unsafe { *offsets.as_ptr().add(rank_key >> OFFSET_SHIFT) as usize };
             let hash_key = rank_key.wrapping_add(offset);
-            unsafe { *LOOKUP.get_unchecked(hash_key) }
+            unsafe { *lookup.as_ptr().add(hash_key) }
+        }
+    }
+
+    /// Returns hand strength of the best five-card hand in `self` on the same scale as
+    /// `evaluate()`. Unlike `evaluate()`, `self` may consist of 5-9 cards (e.g., big-board
+    /// games and Omaha brute force); 8- and 9-card hands are reduced to their 7-card subsets.
+    /// The reduction is brute force: the maximum of `evaluate()` over the 8 (or 36) subsets.
+    pub fn evaluate_n(&self) -> u16 {
+        let len = self.len();
+        assert!(
+            (5..=9).contains(&len),
+            "evaluate_n() requires 5-9 cards, but got {}",
+            len
+        );
+        if len <= 7 {
+            return self.evaluate();
+        }
+
+        let mut cards = [0; 9];
+        let mut mask = self.mask;
+        for card in cards.iter_mut().take(len) {
+            let bit = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            *card = (bit % 16) * 4 + bit / 16;
+        }
+
+        let mut best = 0;
+        if len == 8 {
+            for &card in &cards[..8] {
+                best = best.max(self.remove_card(card).evaluate());
+            }
+        } else {
+            for i in 0..8 {
+                let hand = self.remove_card(cards[i]);
+                for &card in &cards[(i + 1)..9] {
+                    best = best.max(hand.remove_card(card).evaluate());
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns hand strength of a partial hand of 1-4 cards (e.g., hole cards or a partial
+    /// board). The hand category (high card, one pair, two pair, three of a kind or four of a
+    /// kind) is encoded in the upper 4 bits as in `evaluate()`, so `get_hand_category()` works,
+    /// and stronger ranks yield higher values. Within a category, the values are comparable
+    /// only among partial hands, and a hand with an extra kicker ranks above the same hand
+    /// without it.
+    pub fn evaluate_partial(&self) -> u16 {
+        let len = self.len();
+        assert!(
+            (1..=4).contains(&len),
+            "evaluate_partial() requires 1-4 cards, but got {}",
+            len
+        );
+
+        // rankset_of_count[i]: set of ranks that appear exactly `i` times
+        let mut rankset_of_count = [0u16; 5];
+        for rank in 0..NUMBER_OF_RANKS {
+            let count = ((self.mask >> rank) & 0x0001_0001_0001_0001).count_ones();
+            rankset_of_count[count as usize] |= 1 << rank;
+        }
+
+        let (category, groups) = match rankset_of_count {
+            [_, _, _, _, quads] if quads > 0 => (HandCategory::FourOfAKind, quads),
+            [_, _, _, trips, _] if trips > 0 => (HandCategory::ThreeOfAKind, trips),
+            [_, _, pairs, _, _] if pairs.count_ones() == 2 => (HandCategory::TwoPair, pairs),
+            [_, _, pairs, _, _] if pairs > 0 => (HandCategory::OnePair, pairs),
+            _ => (HandCategory::HighCard, 0),
+        };
+
+        // ranks of groups are compared first, followed by kickers
+        let num_kickers = rankset_of_count[1].count_ones() as usize;
+        let num_groups = groups.count_ones() as usize;
+        let num_kicker_ranks = NUMBER_OF_RANKS - num_groups;
+        let max_kickers = 4 - (len - num_kickers);
+        let kickers = compress_rankset(rankset_of_count[1], groups);
+        let groups_index = rankset_index(groups, num_groups);
+        let kickers_index = rankset_index(kickers, max_kickers);
+        let index = groups_index * num_ranksets(num_kicker_ranks, max_kickers) + kickers_index;
+        ((category as u16) << 12) | index as u16
+    }
+}
+
+/// Removes the ranks in `removed` from `rankset` and shifts the higher ranks down.
+fn compress_rankset(rankset: u16, removed: u16) -> u16 {
+    let mut result = 0;
+    let mut shift = 0;
+    for rank in 0..NUMBER_OF_RANKS {
+        if removed & (1 << rank) != 0 {
+            shift += 1;
+        } else if rankset & (1 << rank) != 0 {
+            result |= 1 << (rank - shift);
         }
     }
+    result
+}
+
+/// Returns the number of rank sets of size at most `max_len` whose ranks are less than `n`.
+fn num_ranksets(n: usize, max_len: usize) -> usize {
+    let mut binom = 1;
+    let mut result = 1;
+    for k in 1..=max_len.min(n) {
+        binom = binom * (n + 1 - k) / k;
+        result += binom;
+    }
+    result
+}
+
+/// Returns the index of `rankset` among rank sets of size at most `max_len`, where rank sets are
+/// compared lexicographically from the highest rank and a prefix precedes its extensions.
+fn rankset_index(rankset: u16, max_len: usize) -> usize {
+    let mut index = 0;
+    let mut remaining = max_len;
+    let mut rankset = rankset;
+    while rankset != 0 {
+        let rank = 15 - rankset.leading_zeros() as usize;
+        rankset ^= 1 << rank;
+        remaining -= 1;
+        // the prefix itself and the rank sets continuing with lower ranks precede
+        index += 1 + (0..rank).map(|r| num_ranksets(r, remaining)).sum::<usize>();
+    }
+    index
+}
+
+/// Returns the cards not included in `mask` in ascending order (only the first `len` elements
+/// are valid).
+pub(crate) fn compute_alive_cards(mask: u64) -> ([usize; NUMBER_OF_CARDS], usize) {
+    let mut result = [0; NUMBER_OF_CARDS];
+    let mut len = 0;
+    for (card, &(_, card_mask)) in CARDS.iter().enumerate() {
+        if (card_mask & mask) == 0 {
+            result[len] = card;
+            len += 1;
+        }
+    }
+    (result, len)
+}
+
+/// Returns `mask` with its 16-bit suit groups sorted, so that hands equivalent up to a suit
+/// permutation have the same value.
+#[cfg(feature = "std")]
+pub(crate) fn canonical_mask(mask: u64) -> u64 {
+    let mut suits = [
+        mask & 0xffff,
+        (mask >> 16) & 0xffff,
+        (mask >> 32) & 0xffff,
+        (mask >> 48) & 0xffff,
+    ];
+    suits.sort_unstable();
+    suits[0] | (suits[1] << 16) | (suits[2] << 32) | (suits[3] << 48)
 }
 
 impl Add for Hand {
     type Output = Self;
+    // the initial suit counters are included in both keys, so one copy is subtracted
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn add(self, rhs: Self) -> Self::Output {
         Self {
             key: self
@@ -140,6 +352,7 @@ impl Add for Hand {
 }
 
 impl AddAssign for Hand {
+    #[allow(clippy::suspicious_op_assign_impl)]
     fn add_assign(&mut self, rhs: Self) {
         self.key = self.key.wrapping_add(rhs.key);
         self.key = self.key.wrapping_sub(0x3333 << SUIT_SHIFT);
@@ -153,6 +366,7 @@ impl Default for Hand {
     }
 }
 
+#[cfg(feature = "std")]
 impl FromStr for Hand {
     type Err = String;
 
@@ -202,10 +416,10 @@ impl FromStr for Hand {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
-    use std::collections::HashSet;
+    use std::collections::{BTreeMap, HashSet};
 
     fn evaluate_hand_str(hand_str: &str) -> u16 {
         let hand = hand_str.parse::<Hand>().unwrap();
@@ -354,7 +568,9 @@ mod tests {
         assert_eq!(counter[HandCategory::HighCard as usize], 23294460);
     }
 
+    // `(c << 12) + 0` keeps the category and the index visible
     #[test]
+    #[allow(clippy::identity_op)]
     fn test_edge_cases() {
         // straight flushes
         assert_eq!(evaluate_hand_str("AsKsQsJsTs7d5s"), (8 << 12) + 9);
@@ -396,6 +612,112 @@ mod tests {
     }
 
     #[test]
+    fn test_pair_rank() {
+        assert_eq!(get_pair_rank(evaluate_hand_str("AdAsKhQdJs3s2c")), Some(12));
+        assert_eq!(get_pair_rank(evaluate_hand_str("JdJs9h8d6s3s2c")), Some(9));
+        assert_eq!(get_pair_rank(evaluate_hand_str("TdTsAhKdQs3s2c")), Some(8));
+        assert_eq!(get_pair_rank(evaluate_hand_str("8s7s5h4c3c2d2c")), Some(0));
+        assert_eq!(get_pair_rank(evaluate_hand_str("AsAhKsKhQsQhJs")), None);
+    }
+
+    #[test]
+    fn test_evaluate_partial() {
+        // naive key: category, ranks of groups and kickers (a prefix precedes its extensions)
+        fn naive_key(cards: &[usize]) -> (HandCategory, Vec<usize>, Vec<usize>) {
+            let mut count = [0; 13];
+            for card in cards {
+                count[card / 4] += 1;
+            }
+            let max_count = *count.iter().max().unwrap();
+            let groups = (0..13)
+                .rev()
+                .filter(|&r| max_count >= 2 && count[r] == max_count);
+            let groups = groups.collect::<Vec<_>>();
+            let kickers = (0..13).rev().filter(|&r| count[r] == 1).collect();
+            let category = match (max_count, groups.len()) {
+                (4, _) => HandCategory::FourOfAKind,
+                (3, _) => HandCategory::ThreeOfAKind,
+                (2, 2) => HandCategory::TwoPair,
+                (2, _) => HandCategory::OnePair,
+                _ => HandCategory::HighCard,
+            };
+            (category, groups, kickers)
+        }
+
+        fn rec(
+            cards: &mut Vec<usize>,
+            start: usize,
+            map: &mut BTreeMap<u16, (HandCategory, Vec<usize>, Vec<usize>)>,
+        ) {
+            if !cards.is_empty() {
+                let rank = Hand::from_slice(cards).evaluate_partial();
+                let key = naive_key(cards);
+                assert_eq!(get_hand_category(rank), key.0);
+                assert_eq!(*map.entry(rank).or_insert_with(|| key.clone()), key);
+            }
+            if cards.len() < 4 {
+                for card in start..NUMBER_OF_CARDS {
+                    cards.push(card);
+                    rec(cards, card + 1, map);
+                    cards.pop();
+                }
+            }
+        }
+
+        let mut map = BTreeMap::new();
+        rec(&mut Vec::new(), 0, &mut map);
+        assert!(map.values().zip(map.values().skip(1)).all(|(a, b)| a < b));
+
+        let evaluate = |s: &str| s.parse::<Hand>().unwrap().evaluate_partial();
+        assert!(evaluate("AsAh") > evaluate("KsKhQd"));
+        assert!(evaluate("AsAhKd") > evaluate("AsAh"));
+        assert!(evaluate("2c2d") > evaluate("AsKhQdJc"));
+        assert!(evaluate("AsKh") > evaluate("KsQhJdTc"));
+        assert_eq!(
+            get_hand_category(evaluate("7c7d7h")),
+            HandCategory::ThreeOfAKind
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_evaluate_partial_too_many_cards() {
+        "AsKsQsJsTs".parse::<Hand>().unwrap().evaluate_partial();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_evaluate_too_few_cards() {
+        "AsKs".parse::<Hand>().unwrap().evaluate();
+    }
+
+    #[test]
+    #[cfg(not(feature = "runtime-tables"))]
+    fn test_const_evaluation() {
+        const ROYAL_FLUSH: Hand = Hand::from_slice(&[32, 36, 40, 44, 48]);
+        const ACES: Hand = Hand::new().add_card(51).add_card(50);
+        const ACES_FULL: Hand = Hand::from_slice(&[49, 45, 44]).add_card(51).add_card(50);
+        const RANKS: [u16; 2] = [ROYAL_FLUSH.evaluate(), ACES_FULL.evaluate()];
+        const CATEGORY: HandCategory = get_hand_category(RANKS[1]);
+        const _: () = assert!(ACES.len() == 2 && ACES.contains(51) && !ACES.contains(49));
+        const _: () = assert!(
+            ACES_FULL
+                .remove_card(49)
+                .remove_card(45)
+                .remove_card(44)
+                .get_mask()
+                == ACES.get_mask()
+        );
+
+        assert_eq!(ROYAL_FLUSH, "TcJcQcKcAc".parse().unwrap());
+        assert_eq!(RANKS[0], (8 << 12) + 9);
+        assert_eq!(RANKS[1], "AsAhAdKcKd".parse::<Hand>().unwrap().evaluate());
+        assert_eq!(CATEGORY, HandCategory::FullHouse);
+    }
+
+    #[test]
+    #[allow(clippy::identity_op)]
     fn test_hand_addition() {
         let hand1 = "4h4c".parse::<Hand>().unwrap();
         let hand2 = "5h4s".parse::<Hand>().unwrap();
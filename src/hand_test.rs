@@ -1,5 +1,6 @@
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
+    use crate::rng::Rng;
     use crate::Hand;
     use assets::constants::NUMBER_OF_CARDS;
 
@@ -32,6 +33,7 @@ mod tests {
         }
     }
 
+    #[allow(clippy::identity_op)]
     fn evaluate_hand_naive(hand: &[usize]) -> u32 {
         let mut rankset: u32 = 0;
         let mut rankset_suit: [u32; 4] = [0; 4];
@@ -57,32 +59,37 @@ mod tests {
             }
         }
 
-        if is_flush >= 0 {
-            match find_straight(rankset_suit[is_flush as usize]) {
-                // straight flush
-                Some(x) => (8 << 26) | x,
-                // flush
-                None => (5 << 26) | keep_n_msb(rankset_suit[is_flush as usize], 5),
-            }
+        let straight_flush = match is_flush {
+            -1 => None,
+            i => find_straight(rankset_suit[i as usize]),
+        };
+
+        if let Some(x) = straight_flush {
+            // straight flush
+            (8 << 26) | x
         } else if rankset_of_count[4] > 0 {
             // four of a kind
-            let remaining = keep_n_msb(rankset ^ rankset_of_count[4], 1);
-            (7 << 26) | (rankset_of_count[4] << 13) | remaining
-        } else if rankset_of_count[3].count_ones() == 2 {
+            let quads = keep_n_msb(rankset_of_count[4], 1);
+            let remaining = keep_n_msb(rankset ^ quads, 1);
+            (7 << 26) | (quads << 13) | remaining
+        } else if rankset_of_count[3].count_ones() >= 2 {
             // full house
             let trips = keep_n_msb(rankset_of_count[3], 1);
-            let pair = rankset_of_count[3] ^ trips;
+            let pair = keep_n_msb((rankset_of_count[3] ^ trips) | rankset_of_count[2], 1);
             (6 << 26) | (trips << 13) | pair
         } else if rankset_of_count[3] > 0 && rankset_of_count[2] > 0 {
             // full house
             let pair = keep_n_msb(rankset_of_count[2], 1);
             (6 << 26) | (rankset_of_count[3] << 13) | pair
+        } else if is_flush >= 0 {
+            // flush (8- and 9-card hands may contain a flush and a full house)
+            (5 << 26) | keep_n_msb(rankset_suit[is_flush as usize], 5)
         } else if let Some(x) = find_straight(rankset) {
             // straight
             (4 << 26) | x
         } else if rankset_of_count[3] > 0 {
             // three of a kind
-            let remaining = keep_n_msb(rankset_of_count[1], 2);
+            let remaining = keep_n_msb(rankset ^ rankset_of_count[3], 2);
             (3 << 26) | (rankset_of_count[3] << 13) | remaining
         } else if rankset_of_count[2].count_ones() >= 2 {
             // two pair
@@ -91,7 +98,7 @@ mod tests {
             (2 << 26) | (pairs << 13) | remaining
         } else if rankset_of_count[2] > 0 {
             // one pair
-            let remaining = keep_n_msb(rankset_of_count[1], 3);
+            let remaining = keep_n_msb(rankset ^ rankset_of_count[2], 3);
             (1 << 26) | (rankset_of_count[2] << 13) | remaining
         } else {
             // high card
@@ -147,4 +154,151 @@ mod tests {
             }
         }
     }
+
+    fn check_consistency(table: &mut [u32], rank: u16, rank_naive: u32) {
+        if table[rank as usize] == 0 {
+            table[rank as usize] = rank_naive;
+        } else {
+            assert_eq!(table[rank as usize], rank_naive);
+        }
+    }
+
+    fn check_monotonicity(table: &[u32]) {
+        let mut prev_rank = 0;
+        for rank_naive in table {
+            if *rank_naive > 0 {
+                assert!(prev_rank < *rank_naive);
+                prev_rank = *rank_naive;
+            }
+        }
+    }
+
+    /// Checks `evaluate_n()` against the naive evaluator for all `n`-card combinations of `deck`.
+    fn check_all_combinations(deck: &[usize], n: usize, table: &mut [u32]) {
+        fn rec(deck: &[usize], n: usize, start: usize, cards: &mut Vec<usize>, table: &mut [u32]) {
+            if cards.len() == n {
+                let rank = Hand::from_slice(cards).evaluate_n();
+                check_consistency(table, rank, evaluate_hand_naive(cards));
+                return;
+            }
+            for i in start..=(deck.len() + cards.len() - n) {
+                cards.push(deck[i]);
+                rec(deck, n, i + 1, cards, table);
+                cards.pop();
+            }
+        }
+        rec(deck, n, 0, &mut Vec::with_capacity(n), table);
+    }
+
+    #[test]
+    fn test_naive_8_9_cards() {
+        let mut table = vec![0; 32780];
+
+        // A23456 of all suits: quads, full houses, wheels and straight flushes
+        let deck = (0..5 * 4).chain(48..52).collect::<Vec<_>>();
+        check_all_combinations(&deck, 8, &mut table);
+        check_all_combinations(&deck, 9, &mut table);
+
+        // all ranks of two suits: flushes, straights and high cards
+        let deck = (0..NUMBER_OF_CARDS)
+            .filter(|c| c % 4 < 2)
+            .collect::<Vec<_>>();
+        check_all_combinations(&deck, 8, &mut table);
+
+        // random hands from the full deck
+        let mut rng = Rng::new(0);
+        for _ in 0..1_000_000 {
+            let n = 8 + rng.gen_below(2);
+            let mut cards = Vec::with_capacity(n);
+            while cards.len() < n {
+                let card = rng.gen_below(NUMBER_OF_CARDS);
+                if !cards.contains(&card) {
+                    cards.push(card);
+                }
+            }
+            let rank = Hand::from_slice(&cards).evaluate_n();
+            check_consistency(&mut table, rank, evaluate_hand_naive(&cards));
+        }
+
+        check_monotonicity(&table);
+    }
+
+    #[test]
+    fn test_evaluate_n_5_6_cards() {
+        let mut table = vec![0; 32780];
+        let deck = (0..NUMBER_OF_CARDS).collect::<Vec<_>>();
+        check_all_combinations(&deck, 5, &mut table);
+        check_all_combinations(&deck, 6, &mut table);
+        check_monotonicity(&table);
+    }
+
+    /// Checks `evaluate_n()` against the naive evaluator for one hand per rank counts and set of
+    /// flush ranks (the hand strength does not depend on the suits otherwise).
+    fn check_rank_counts(counts: &[usize; 13], table: &mut [u32]) {
+        let ranks = (0..13).filter(|&r| counts[r] > 0).collect::<Vec<_>>();
+        for flush in 0..(1u32 << ranks.len()) {
+            let num_flush = flush.count_ones();
+            if num_flush != 0 && num_flush < 5 {
+                continue;
+            }
+            let mut cards = Vec::new();
+            let mut next_suit = 0;
+            for (i, &r) in ranks.iter().enumerate() {
+                let is_flush_rank = (flush >> i) & 1 != 0;
+                for copy in 0..counts[r] {
+                    // without a flush, suits are assigned cyclically (at most 2 cards per suit);
+                    // otherwise, the other cards (at most 3) avoid the flush suit 0 if possible
+                    let suit = if num_flush == 0 {
+                        next_suit % 4
+                    } else if (is_flush_rank && copy == 0) || (!is_flush_rank && copy == 3) {
+                        0
+                    } else {
+                        next_suit % 3 + 1
+                    };
+                    if num_flush == 0 || suit != 0 {
+                        next_suit += 1;
+                    }
+                    cards.push(r * 4 + suit);
+                }
+            }
+            let rank = Hand::from_slice(&cards).evaluate_n();
+            check_consistency(table, rank, evaluate_hand_naive(&cards));
+        }
+    }
+
+    #[test]
+    fn test_evaluate_n_8cards() {
+        fn rec(counts: &mut [usize; 13], rank: usize, remaining: usize, table: &mut [u32]) {
+            if rank == 13 {
+                if remaining == 0 {
+                    check_rank_counts(counts, table);
+                }
+                return;
+            }
+            for c in 0..=remaining.min(4) {
+                counts[rank] = c;
+                rec(counts, rank + 1, remaining - c, table);
+            }
+            counts[rank] = 0;
+        }
+        let mut table = vec![0; 32780];
+        rec(&mut [0; 13], 0, 8, &mut table);
+        check_monotonicity(&table);
+    }
+
+    #[test]
+    fn test_evaluate_n_matches_evaluate() {
+        let mut rng = Rng::new(1);
+        for _ in 0..100_000 {
+            let n = 5 + rng.gen_below(3);
+            let mut hand = Hand::new();
+            while hand.len() < n {
+                let card = rng.gen_below(NUMBER_OF_CARDS);
+                if !hand.contains(card) {
+                    hand = hand.add_card(card);
+                }
+            }
+            assert_eq!(hand.evaluate_n(), hand.evaluate());
+        }
+    }
 }
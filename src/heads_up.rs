@@ -1,63 +1,113 @@
+use crate::error::*;
 use crate::hand::*;
 use assets::constants::*;
 use assets::heads_up::HEADS_UP_WIN_FREQUENCY;
 
+type WinFrequencyFn = fn(&Hand, &Hand, &[usize]) -> (u32, u32, u32);
+
 /// Computes heads-up win frequency.
 /// Return value: (# of `hand1` wins, # of `hand2` wins, # of tie)
+///
+/// Panics on invalid input; see `try_heads_up_win_frequency()`.
 pub fn heads_up_win_frequency(
     hand1: &Hand,
     hand2: &Hand,
     board: &Hand,
     dead_cards: &Hand,
 ) -> (u32, u32, u32) {
-    assert_eq!(hand1.len(), 2);
-    assert!(hand2.len() <= 2);
-    assert!(board.len() == 0 || board.len() == 3 || board.len() == 4 || board.len() == 5);
-    assert_eq!(
-        (*hand1 + *hand2 + *board + *dead_cards).len(),
-        hand1.len() + hand2.len() + board.len() + dead_cards.len()
-    );
-    let alive_cards = compute_alive_cards(
+    try_heads_up_win_frequency(hand1, hand2, board, dead_cards).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Computes heads-up win frequency, or returns an error when `hand1` does not consist of 2
+/// cards, `hand2` consists of more than 2 cards (missing cards are dealt from the deck),
+/// `board` does not consist of 0, 3, 4 or 5 cards, the hands share a card, or not enough cards
+/// remain.
+/// Return value: (# of `hand1` wins, # of `hand2` wins, # of tie)
+pub fn try_heads_up_win_frequency(
+    hand1: &Hand,
+    hand2: &Hand,
+    board: &Hand,
+    dead_cards: &Hand,
+) -> Result<(u32, u32, u32), EquityError> {
+    if hand1.len() != 2 {
+        return Err(EquityError::InvalidHoleCards(hand1.len()));
+    }
+    if hand2.len() > 2 {
+        return Err(EquityError::InvalidHoleCards(hand2.len()));
+    }
+    if !matches!(board.len(), 0 | 3 | 4 | 5) {
+        return Err(EquityError::InvalidBoardSize(board.len()));
+    }
+    if (*hand1 + *hand2 + *board + *dead_cards).len()
+        != hand1.len() + hand2.len() + board.len() + dead_cards.len()
+    {
+        return Err(EquityError::OverlappingCards);
+    }
+    let (alive_cards, num_alive_cards) = compute_alive_cards(
         hand1.get_mask() | hand2.get_mask() | board.get_mask() | dead_cards.get_mask(),
     );
-    assert!(alive_cards.len() >= 5 - board.len());
+    let alive_cards = &alive_cards[..num_alive_cards];
+    if alive_cards.len() < (5 - board.len()) + (2 - hand2.len()) {
+        return Err(EquityError::NotEnoughLiveCards);
+    }
+    Ok(heads_up_win_frequency_unchecked(
+        hand1,
+        hand2,
+        board,
+        dead_cards,
+        alive_cards,
+    ))
+}
+
+fn heads_up_win_frequency_unchecked(
+    hand1: &Hand,
+    hand2: &Hand,
+    board: &Hand,
+    dead_cards: &Hand,
+    alive_cards: &[usize],
+) -> (u32, u32, u32) {
     let hand1 = *hand1 + *board;
     let hand2 = *hand2 + *board;
     match (hand2.len() - board.len(), board.len()) {
         (0, 0) => match dead_cards.len() {
             0 => heads_up_win_freq_0_0(&hand1),
-            _ => heads_up_win_freq_0(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_0),
+            _ => heads_up_win_freq_0(&hand1, &hand2, alive_cards, heads_up_win_freq_2_0),
         },
-        (0, 3) => heads_up_win_freq_0(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_3),
-        (0, 4) => heads_up_win_freq_0(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_4),
-        (0, 5) => heads_up_win_freq_0(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_5),
-        (1, 0) => heads_up_win_freq_1(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_0),
-        (1, 3) => heads_up_win_freq_1(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_3),
-        (1, 4) => heads_up_win_freq_1(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_4),
-        (1, 5) => heads_up_win_freq_1(&hand1, &hand2, &alive_cards, heads_up_win_freq_2_5),
-        (2, 0) => heads_up_win_freq_2_0(&hand1, &hand2, &alive_cards),
-        (2, 3) => heads_up_win_freq_2_3(&hand1, &hand2, &alive_cards),
-        (2, 4) => heads_up_win_freq_2_4(&hand1, &hand2, &alive_cards),
-        (2, 5) => heads_up_win_freq_2_5(&hand1, &hand2, &alive_cards),
+        (0, 3) => heads_up_win_freq_0(&hand1, &hand2, alive_cards, heads_up_win_freq_2_3),
+        (0, 4) => heads_up_win_freq_0(&hand1, &hand2, alive_cards, heads_up_win_freq_2_4),
+        (0, 5) => heads_up_win_freq_0(&hand1, &hand2, alive_cards, heads_up_win_freq_2_5),
+        (1, 0) => heads_up_win_freq_1(&hand1, &hand2, alive_cards, heads_up_win_freq_2_0),
+        (1, 3) => heads_up_win_freq_1(&hand1, &hand2, alive_cards, heads_up_win_freq_2_3),
+        (1, 4) => heads_up_win_freq_1(&hand1, &hand2, alive_cards, heads_up_win_freq_2_4),
+        (1, 5) => heads_up_win_freq_1(&hand1, &hand2, alive_cards, heads_up_win_freq_2_5),
+        (2, 0) => heads_up_win_freq_2_0(&hand1, &hand2, alive_cards),
+        (2, 3) => heads_up_win_freq_2_3(&hand1, &hand2, alive_cards),
+        (2, 4) => heads_up_win_freq_2_4(&hand1, &hand2, alive_cards),
+        (2, 5) => heads_up_win_freq_2_5(&hand1, &hand2, alive_cards),
         _ => unreachable!(),
     }
 }
 
-fn compute_alive_cards(mask: u64) -> Vec<usize> {
-    let mut result = Vec::new();
-    for i in 0..NUMBER_OF_CARDS {
-        if (CARDS[i].1 & mask) == 0 {
-            result.push(i);
+/// Returns `alive_cards` without the elements at `excluded` indices.
+fn remove_indices(alive_cards: &[usize], excluded: &[usize]) -> ([usize; NUMBER_OF_CARDS], usize) {
+    let mut result = [0; NUMBER_OF_CARDS];
+    let mut len = 0;
+    for (idx, &card) in alive_cards.iter().enumerate() {
+        if !excluded.contains(&idx) {
+            result[len] = card;
+            len += 1;
         }
     }
-    result
+    (result, len)
 }
 
 fn heads_up_win_freq_0_0(hand: &Hand) -> (u32, u32, u32) {
-    let mut cards = Vec::new();
-    for i in 0..NUMBER_OF_CARDS {
-        if (CARDS[i].1 & hand.get_mask()) != 0 {
-            cards.push(i);
+    let mut cards = [0; 2];
+    let mut len = 0;
+    for (card, &(_, card_mask)) in CARDS.iter().enumerate() {
+        if (card_mask & hand.get_mask()) != 0 {
+            cards[len] = card;
+            len += 1;
         }
     }
     let rank1 = cards[0] / 4;
@@ -75,7 +125,7 @@ fn heads_up_win_freq_0(
     hand1: &Hand,
     hand2: &Hand,
     alive_cards: &[usize],
-    func: fn(&Hand, &Hand, &[usize]) -> (u32, u32, u32),
+    func: WinFrequencyFn,
 ) -> (u32, u32, u32) {
     let len = alive_cards.len();
     let mut result = (0, 0, 0);
@@ -83,15 +133,8 @@ fn heads_up_win_freq_0(
         let hand2 = hand2.add_card(alive_cards[i]);
         for j in (i + 1)..len {
             let hand2 = hand2.add_card(alive_cards[j]);
-            let alive_cards = alive_cards
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, x)| match idx {
-                    _ if idx == i || idx == j => None,
-                    _ => Some(*x),
-                })
-                .collect::<Vec<usize>>();
-            let tmp = func(hand1, &hand2, &alive_cards);
+            let (alive_cards, len) = remove_indices(alive_cards, &[i, j]);
+            let tmp = func(hand1, &hand2, &alive_cards[..len]);
             result.0 += tmp.0;
             result.1 += tmp.1;
             result.2 += tmp.2;
@@ -104,21 +147,14 @@ fn heads_up_win_freq_1(
     hand1: &Hand,
     hand2: &Hand,
     alive_cards: &[usize],
-    func: fn(&Hand, &Hand, &[usize]) -> (u32, u32, u32),
+    func: WinFrequencyFn,
 ) -> (u32, u32, u32) {
     let len = alive_cards.len();
     let mut result = (0, 0, 0);
     for i in 0..len {
         let hand2 = hand2.add_card(alive_cards[i]);
-        let alive_cards = alive_cards
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, x)| match idx {
-                _ if idx == i => None,
-                _ => Some(*x),
-            })
-            .collect::<Vec<usize>>();
-        let tmp = func(hand1, &hand2, &alive_cards);
+        let (alive_cards, len) = remove_indices(alive_cards, &[i]);
+        let tmp = func(hand1, &hand2, &alive_cards[..len]);
         result.0 += tmp.0;
         result.1 += tmp.1;
         result.2 += tmp.2;
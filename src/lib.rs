@@ -1,7 +1,59 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+mod badugi;
+mod batch;
+#[cfg(feature = "std")]
+mod board;
+mod deck;
+// the enumeration loops index `alive_cards` so that each inner loop starts after the outer one
+#[allow(clippy::needless_range_loop)]
 mod enumerate;
+mod error;
 mod hand;
 mod hand_test;
+#[allow(clippy::needless_range_loop)]
 mod heads_up;
+mod low;
+#[cfg(feature = "std")]
+mod ofc;
+#[cfg(feature = "std")]
+mod pineapple;
+mod rng;
+#[cfg(feature = "std")]
+mod showdown;
+mod simd;
+#[cfg(feature = "std")]
+mod stud;
+mod tables;
+#[cfg(feature = "std")]
+mod three_card;
+#[cfg(feature = "std")]
+mod video_poker;
+mod wild;
+#[cfg(feature = "std")]
+pub use badugi::*;
+pub use batch::*;
+#[cfg(feature = "std")]
+pub use board::*;
+pub use deck::*;
 pub use enumerate::*;
+pub use error::*;
 pub use hand::*;
 pub use heads_up::*;
+pub use low::*;
+#[cfg(feature = "std")]
+pub use ofc::*;
+#[cfg(feature = "std")]
+pub use pineapple::*;
+pub use rng::*;
+#[cfg(feature = "std")]
+pub use showdown::*;
+pub use simd::*;
+#[cfg(feature = "std")]
+pub use stud::*;
+#[cfg(feature = "std")]
+pub use three_card::*;
+#[cfg(feature = "std")]
+pub use video_poker::*;
+pub use wild::*;
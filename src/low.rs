@@ -0,0 +1,51 @@
+use crate::hand::*;
+
+/// Returns ace-to-five low strength of `hand` under the eight-or-better qualifier.
+/// Straights and flushes are ignored and aces play low; the return value is 0 when `hand` has
+/// no qualifying low, otherwise a stronger low yields a higher value (A-2-3-4-5 is the best).
+#[inline]
+pub fn evaluate_low_8_or_better(hand: &Hand) -> u16 {
+    let mask = hand.get_mask();
+    let rankset = (mask | (mask >> 16) | (mask >> 32) | (mask >> 48)) as u16 & 0x1fff;
+
+    // bit 0 = ace, bit 1 = deuce, ..., bit 7 = eight
+    let mut lowset = ((rankset << 1) | (rankset >> 12)) & 0xff;
+    if lowset.count_ones() < 5 {
+        return 0;
+    }
+    while lowset.count_ones() > 5 {
+        lowset &= !(1 << (15 - lowset.leading_zeros()));
+    }
+
+    // 5 of 8 bits are set, so the complement is never zero
+    !lowset & 0xff
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn evaluate_low_str(hand_str: &str) -> u16 {
+        evaluate_low_8_or_better(&hand_str.parse::<Hand>().unwrap())
+    }
+
+    #[test]
+    fn test_low_8_or_better() {
+        // no qualifying low
+        assert_eq!(evaluate_low_str("9c8d7h6sKcKdQh"), 0);
+        assert_eq!(evaluate_low_str("AcAd2h2s3c3d4h"), 0);
+
+        // best and worst qualifying lows
+        assert_eq!(evaluate_low_str("5c4d3h2sAc"), 0b1110_0000);
+        assert_eq!(evaluate_low_str("8c7d6h5s4c"), 0b0000_0111);
+
+        // pairs, straights and flushes do not matter
+        assert_eq!(
+            evaluate_low_str("5c4c3c2cAcAdKs"),
+            evaluate_low_str("5c4d3h2sAc")
+        );
+        assert!(evaluate_low_str("6c4d3h2sAcKsKd") < evaluate_low_str("5c4d3h2sAc"));
+        assert!(evaluate_low_str("7c4d3h2sAc8s8d") > evaluate_low_str("7c5d3h2sAc"));
+        assert!(evaluate_low_str("8c5d4h3s2c") < evaluate_low_str("7c6d5h4s3c"));
+    }
+}
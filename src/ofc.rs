@@ -0,0 +1,337 @@
+use crate::error::*;
+use crate::hand::*;
+use assets::constants::*;
+
+/// Board of Open-face Chinese poker: 3-card front, 5-card middle and 5-card back rows.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct OfcBoard {
+    front: Hand,
+    middle: Hand,
+    back: Hand,
+}
+
+/// Returns strength of an OFC row (3 or 5 cards) that is comparable across rows.
+/// Missing kickers of the 3-card front row are treated as lower than any card, and only high
+/// cards, pairs and three of a kinds are counted in the front row.
+pub fn evaluate_ofc_row(hand: &Hand) -> u32 {
+    let mask = hand.get_mask();
+    let mut count = [0; NUMBER_OF_RANKS];
+    for (rank, c) in count.iter_mut().enumerate() {
+        *c = (0..4)
+            .filter(|suit| (mask >> (16 * suit + rank)) & 1 != 0)
+            .count();
+    }
+
+    let (category, straight_top) = match hand.len() {
+        3 => match count.iter().max().unwrap() {
+            3 => (HandCategory::ThreeOfAKind, None),
+            2 => (HandCategory::OnePair, None),
+            _ => (HandCategory::HighCard, None),
+        },
+        5 => {
+            let rank = hand.evaluate();
+            match get_hand_category(rank) {
+                c @ HandCategory::Straight | c @ HandCategory::StraightFlush => {
+                    (c, Some((rank & 0xfff) as usize + 3))
+                }
+                c => (c, None),
+            }
+        }
+        _ => panic!("OFC rows must consist of 3 or 5 cards"),
+    };
+
+    // ranks ordered by (count, rank) in descending order; 0 means no card
+    let mut key = (category as u32) << 20;
+    let mut shift = 20;
+    match straight_top {
+        Some(top) => key |= (top as u32 + 1) << 16,
+        None => {
+            for c in (1..=4).rev() {
+                for rank in (0..NUMBER_OF_RANKS).rev().filter(|&r| count[r] == c) {
+                    shift -= 4;
+                    key |= (rank as u32 + 1) << shift;
+                }
+            }
+        }
+    }
+    key
+}
+
+impl OfcBoard {
+    /// Creates a new board. `front` must have 3 cards, and `middle` and `back` must have 5 cards.
+    ///
+    /// Panics on invalid input; see `OfcBoard::try_new()`.
+    pub fn new(front: Hand, middle: Hand, back: Hand) -> Self {
+        Self::try_new(front, middle, back).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a new board, or returns an error when `front` does not consist of 3 cards,
+    /// `middle` or `back` does not consist of 5 cards, or the rows share a card.
+    pub fn try_new(front: Hand, middle: Hand, back: Hand) -> Result<Self, EquityError> {
+        for (row, len) in [(&front, 3), (&middle, 5), (&back, 5)].iter() {
+            if row.len() != *len {
+                return Err(EquityError::InvalidRowSize(row.len()));
+            }
+        }
+        if (front + middle + back).len() != 13 {
+            return Err(EquityError::OverlappingCards);
+        }
+        Ok(Self {
+            front,
+            middle,
+            back,
+        })
+    }
+
+    /// Returns the front row.
+    #[inline]
+    pub fn front(&self) -> &Hand {
+        &self.front
+    }
+
+    /// Returns the middle row.
+    #[inline]
+    pub fn middle(&self) -> &Hand {
+        &self.middle
+    }
+
+    /// Returns the back row.
+    #[inline]
+    pub fn back(&self) -> &Hand {
+        &self.back
+    }
+
+    /// Returns row strengths of (front, middle, back) computed by `evaluate_ofc_row()`.
+    pub fn row_ranks(&self) -> (u32, u32, u32) {
+        (
+            evaluate_ofc_row(&self.front),
+            evaluate_ofc_row(&self.middle),
+            evaluate_ofc_row(&self.back),
+        )
+    }
+
+    /// Checks whether the board is fouled (rows are not in order of back >= middle >= front).
+    pub fn is_foul(&self) -> bool {
+        let (front, middle, back) = self.row_ranks();
+        !(back >= middle && middle >= front)
+    }
+
+    /// Returns the total royalties of the board (0 if the board is fouled).
+    pub fn royalties(&self) -> i32 {
+        if self.is_foul() {
+            return 0;
+        }
+        front_royalty(&self.front) + middle_royalty(&self.middle) + back_royalty(&self.back)
+    }
+
+    /// Checks whether the board qualifies for fantasyland (front pair of queens or better).
+    pub fn is_fantasyland(&self) -> bool {
+        !self.is_foul() && evaluate_ofc_row(&self.front) >= (1 << 20) | (11 << 16)
+    }
+
+    /// Checks whether the board stays in fantasyland
+    /// (front three of a kind, middle full house or better, or back four of a kind or better).
+    pub fn stays_in_fantasyland(&self) -> bool {
+        let (front, middle, back) = self.row_ranks();
+        !self.is_foul()
+            && (front >> 20 >= HandCategory::ThreeOfAKind as u32
+                || middle >> 20 >= HandCategory::FullHouse as u32
+                || back >> 20 >= HandCategory::FourOfAKind as u32)
+    }
+}
+
+fn front_royalty(hand: &Hand) -> i32 {
+    let rank = evaluate_ofc_row(hand);
+    let top = ((rank >> 16) & 0xf) as i32 - 1;
+    match rank >> 20 {
+        // 66 = 1, ..., AA = 9
+        1 if top >= 4 => top - 3,
+        // 222 = 10, ..., AAA = 22
+        3 => top + 10,
+        _ => 0,
+    }
+}
+
+fn middle_royalty(hand: &Hand) -> i32 {
+    let rank = hand.evaluate();
+    match get_hand_category(rank) {
+        HandCategory::ThreeOfAKind => 2,
+        HandCategory::Straight => 4,
+        HandCategory::Flush => 8,
+        HandCategory::FullHouse => 12,
+        HandCategory::FourOfAKind => 20,
+        HandCategory::StraightFlush if rank == (8 << 12) + 9 => 50,
+        HandCategory::StraightFlush => 30,
+        _ => 0,
+    }
+}
+
+fn back_royalty(hand: &Hand) -> i32 {
+    let rank = hand.evaluate();
+    match get_hand_category(rank) {
+        HandCategory::Straight => 2,
+        HandCategory::Flush => 4,
+        HandCategory::FullHouse => 6,
+        HandCategory::FourOfAKind => 10,
+        HandCategory::StraightFlush if rank == (8 << 12) + 9 => 25,
+        HandCategory::StraightFlush => 15,
+        _ => 0,
+    }
+}
+
+/// Scores a hand of OFC among 2 or 3 players.
+/// Each pair of players compares row by row (1 point per row, plus 3 points for winning all
+/// three rows) and settles the difference of royalties. A fouled board loses all rows to a
+/// non-fouled board and earns no royalties.
+/// Return value: the net points of each player (the sum is always 0)
+pub fn ofc_score(boards: &[OfcBoard]) -> Vec<i32> {
+    assert!(2 <= boards.len() && boards.len() <= 3);
+    let mut result = vec![0; boards.len()];
+    for i in 0..boards.len() {
+        for j in (i + 1)..boards.len() {
+            let points = ofc_score_pair(&boards[i], &boards[j]);
+            result[i] += points;
+            result[j] -= points;
+        }
+    }
+    result
+}
+
+fn ofc_score_pair(board1: &OfcBoard, board2: &OfcBoard) -> i32 {
+    let royalties = board1.royalties() - board2.royalties();
+    let rows = match (board1.is_foul(), board2.is_foul()) {
+        (true, true) => 0,
+        (true, false) => -6,
+        (false, true) => 6,
+        (false, false) => {
+            let (front1, middle1, back1) = board1.row_ranks();
+            let (front2, middle2, back2) = board2.row_ranks();
+            let compare = |x: u32, y: u32| (x > y) as i32 - (x < y) as i32;
+            let points =
+                compare(front1, front2) + compare(middle1, middle2) + compare(back1, back2);
+            match points {
+                3 => 6,
+                -3 => -6,
+                _ => points,
+            }
+        }
+    };
+    rows + royalties
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(front: &str, middle: &str, back: &str) -> OfcBoard {
+        OfcBoard::new(
+            front.parse().unwrap(),
+            middle.parse().unwrap(),
+            back.parse().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_ofc_row() {
+        let row = |s: &str| evaluate_ofc_row(&s.parse().unwrap());
+        assert!(row("AsKdQh") < row("AcKcQc3d2s"));
+        assert!(row("AsKdQh") > row("AcKcJc3d2s"));
+        assert!(row("AsAdKh") < row("AcAhKc3d2s"));
+        assert!(row("AsAdKh") > row("AcAhQc3d2s"));
+        assert!(row("2s2d2h") > row("AcAhKcKd2s"));
+        assert!(row("2s2d2h") < row("3c3h3s4d5s"));
+        assert!(row("Ac2c3d4c5c") < row("2d3c4c5c6c"));
+        assert!(row("Ac2c3d4c5c") > row("KsKhKdQs2h"));
+        assert!(row("Ac2c3c4c5c") > row("KsKhKdKcAh"));
+    }
+
+    #[test]
+    fn test_ofc_row_5cards() {
+        // row strengths of 5-card rows must be consistent with `Hand::evaluate()`
+        let mut keys = vec![None; 1 << 16];
+        for i in 0..(NUMBER_OF_CARDS - 4) {
+            let hand = Hand::new().add_card(i);
+            for j in (i + 1)..(NUMBER_OF_CARDS - 3) {
+                let hand = hand.add_card(j);
+                for k in (j + 1)..(NUMBER_OF_CARDS - 2) {
+                    let hand = hand.add_card(k);
+                    for m in (k + 1)..(NUMBER_OF_CARDS - 1) {
+                        let hand = hand.add_card(m);
+                        for n in (m + 1)..NUMBER_OF_CARDS {
+                            let hand = hand.add_card(n);
+                            let key = evaluate_ofc_row(&hand);
+                            let entry = &mut keys[hand.evaluate() as usize];
+                            assert_eq!(*entry.get_or_insert(key), key);
+                        }
+                    }
+                }
+            }
+        }
+        let keys = keys.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(keys.len(), 7462);
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_foul_and_royalties() {
+        let b = board("QsQd2c", "AsAh3d4c5c", "KsKhKd7s7h");
+        assert!(!b.is_foul());
+        assert_eq!(b.royalties(), 7 + 6);
+        assert!(b.is_fantasyland());
+        assert!(!b.stays_in_fantasyland());
+
+        let b = board("QsQd2c", "JsJh3d4c5c", "KsKhKd7s7h");
+        assert!(b.is_foul());
+        assert_eq!(b.royalties(), 0);
+        assert!(!b.is_fantasyland());
+
+        let b = board("6s6d6c", "9s8s7s5s4s", "AdKdQdJdTd");
+        assert_eq!(b.royalties(), 14 + 8 + 25);
+        assert!(b.stays_in_fantasyland());
+    }
+
+    #[test]
+    fn test_invalid_boards() {
+        let try_board = |front: &str, middle: &str, back: &str| {
+            OfcBoard::try_new(
+                front.parse().unwrap(),
+                middle.parse().unwrap(),
+                back.parse().unwrap(),
+            )
+        };
+        let b = try_board("QsQd2c", "AsAh3d4c5c", "KsKhKd7s7h").unwrap();
+        assert_eq!(b, board("QsQd2c", "AsAh3d4c5c", "KsKhKd7s7h"));
+        assert_eq!(b.front(), &"QsQd2c".parse().unwrap());
+
+        // the rows share As
+        assert_eq!(
+            try_board("QsQd2c", "AsAh3d4c5c", "KsKhKd7sAs"),
+            Err(EquityError::OverlappingCards)
+        );
+        assert_eq!(
+            try_board("QsQd2c3c", "AsAh3d4c5c", "KsKhKd7s7h"),
+            Err(EquityError::InvalidRowSize(4))
+        );
+        assert_eq!(
+            try_board("QsQd2c", "AsAh3d4c", "KsKhKd7s7h"),
+            Err(EquityError::InvalidRowSize(4))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the same card is used more than once")]
+    fn test_overlapping_rows() {
+        board("QsQd2c", "AsAh3d4c5c", "KsKhKd7sAs");
+    }
+
+    #[test]
+    fn test_ofc_score() {
+        let b1 = board("QsQd2c", "AsAh3d4c5c", "KsKhKd7s7h");
+        let b2 = board("JsTd9c", "KcKd8d9d6h", "2s2h2d3s3h");
+        let b3 = board("AcAd2d", "3c4c5d6d7s", "8h8c9h9sTs");
+        // b1 vs b2: 3 rows (6 points) + royalties 13 - 6
+        // b1 vs b3: b3 is fouled (6 points) + royalties 13
+        // b2 vs b3: 6 points + royalties 6
+        assert_eq!(ofc_score(&[b1, b2, b3]), vec![13 + 19, -13 + 12, -19 - 12]);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::hand::*;
+use assets::constants::*;
+
+/// Maximum number of opponents in `pineapple_discard_equity()`.
+pub const MAX_PINEAPPLE_OPPONENTS: usize = 8;
+
+/// Timing of the discard in Pineapple variants.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DiscardTiming {
+    /// The discard is made before the flop (Lazy rule); `board` must be empty.
+    BeforeFlop,
+    /// The discard is made after the flop (Crazy Pineapple); `board` must have 3 cards.
+    AfterFlop,
+}
+
+/// Computes the equity of each discard option of three `hole_cards` by enumerating all boards.
+/// Each element of `opponents` is a range of two-card hands (a known hand is a range with a
+/// single hand), and holdings that conflict with known cards are skipped.
+/// Return value: the expected share of the pot when discarding the `i`-th card of
+/// `hole_cards` in ascending order of card ID (the discarded card is dead), or 0 if every
+/// combination of holdings conflicts with the known cards
+pub fn pineapple_discard_equity(
+    hole_cards: &Hand,
+    board: &Hand,
+    opponents: &[&[Hand]],
+    dead_cards: &Hand,
+    timing: DiscardTiming,
+) -> [f64; 3] {
+    assert_eq!(hole_cards.len(), 3);
+    match timing {
+        DiscardTiming::BeforeFlop => assert_eq!(board.len(), 0),
+        DiscardTiming::AfterFlop => assert_eq!(board.len(), 3),
+    }
+    assert!(!opponents.is_empty() && opponents.len() <= MAX_PINEAPPLE_OPPONENTS);
+    assert!(opponents
+        .iter()
+        .all(|range| range.iter().all(|h| h.len() == 2)));
+    assert_eq!(
+        (*hole_cards + *board + *dead_cards).len(),
+        hole_cards.len() + board.len() + dead_cards.len()
+    );
+
+    let cards = (0..NUMBER_OF_CARDS)
+        .filter(|&c| hole_cards.contains(c))
+        .collect::<Vec<_>>();
+    let mut result = [0.0; 3];
+    if opponents.iter().any(|range| range.is_empty()) {
+        return result;
+    }
+    for (i, &discard) in cards.iter().enumerate() {
+        let mut players = [Hand::new(); MAX_PINEAPPLE_OPPONENTS + 1];
+        players[0] = hole_cards.remove_card(discard);
+        let known_mask = hole_cards.get_mask() | board.get_mask() | dead_cards.get_mask();
+        let num_players = opponents.len() + 1;
+        let mut equity = 0.0;
+        let mut count = 0;
+
+        // holdings of the opponents are enumerated as a mixed-radix counter over the ranges
+        let mut holdings = [0; MAX_PINEAPPLE_OPPONENTS];
+        'holdings: loop {
+            let mut used_mask = known_mask;
+            let mut is_valid = true;
+            for (j, range) in opponents.iter().enumerate() {
+                let hand = range[holdings[j]];
+                is_valid &= (hand.get_mask() & used_mask) == 0;
+                used_mask |= hand.get_mask();
+                players[j + 1] = hand;
+            }
+            if is_valid {
+                let (alive_cards, num_alive_cards) = compute_alive_cards(used_mask);
+                let alive_cards = &alive_cards[..num_alive_cards];
+                assert!(alive_cards.len() >= 5 - board.len());
+                let players = &players[..num_players];
+                let (sum, n) = match board.len() {
+                    0 => pineapple_equity_0(players, board, alive_cards),
+                    3 => pineapple_equity_3(players, board, alive_cards),
+                    _ => unreachable!(),
+                };
+                equity += sum;
+                count += n;
+            }
+            for (j, range) in opponents.iter().enumerate() {
+                holdings[j] += 1;
+                if holdings[j] < range.len() {
+                    continue 'holdings;
+                }
+                holdings[j] = 0;
+            }
+            break;
+        }
+
+        // no holdings remain when every combination conflicts with the known cards
+        if count > 0 {
+            result[i] = equity / count as f64;
+        }
+    }
+    result
+}
+
+/// Returns the share of the pot won by `players[0]` on the complete `board`.
+fn pot_share(players: &[Hand], board: &Hand) -> f64 {
+    let mut ranks = [0; MAX_PINEAPPLE_OPPONENTS + 1];
+    for (rank, player) in ranks.iter_mut().zip(players.iter()) {
+        *rank = (*player + *board).evaluate();
+    }
+    let ranks = &ranks[..players.len()];
+    let best = *ranks.iter().max().unwrap();
+    if ranks[0] == best {
+        1.0 / ranks.iter().filter(|&&rank| rank == best).count() as f64
+    } else {
+        0.0
+    }
+}
+
+#[allow(clippy::needless_range_loop)]
+fn pineapple_equity_0(players: &[Hand], board: &Hand, alive_cards: &[usize]) -> (f64, u64) {
+    let len = alive_cards.len();
+    let mut result = (0.0, 0);
+    for i in 0..(len - 4) {
+        let board = board.add_card(alive_cards[i]);
+        for j in (i + 1)..(len - 3) {
+            let board = board.add_card(alive_cards[j]);
+            for k in (j + 1)..(len - 2) {
+                let board = board.add_card(alive_cards[k]);
+                for m in (k + 1)..(len - 1) {
+                    let board = board.add_card(alive_cards[m]);
+                    for n in (m + 1)..len {
+                        let board = board.add_card(alive_cards[n]);
+                        result.0 += pot_share(players, &board);
+                        result.1 += 1;
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+#[allow(clippy::needless_range_loop)]
+fn pineapple_equity_3(players: &[Hand], board: &Hand, alive_cards: &[usize]) -> (f64, u64) {
+    let len = alive_cards.len();
+    let mut result = (0.0, 0);
+    for i in 0..(len - 1) {
+        let board = board.add_card(alive_cards[i]);
+        for j in (i + 1)..len {
+            let board = board.add_card(alive_cards[j]);
+            result.0 += pot_share(players, &board);
+            result.1 += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heads_up::*;
+
+    fn parse(hand_str: &str) -> Hand {
+        hand_str.parse::<Hand>().unwrap()
+    }
+
+    fn heads_up_equity(hand1: &str, hand2: &str, board: &str, dead_cards: &str) -> f64 {
+        let (win, lose, tie) = heads_up_win_frequency(
+            &parse(hand1),
+            &parse(hand2),
+            &parse(board),
+            &parse(dead_cards),
+        );
+        (win as f64 + tie as f64 / 2.0) / (win + lose + tie) as f64
+    }
+
+    #[test]
+    fn test_after_flop() {
+        let opponent = [parse("KsKd")];
+        let equity = pineapple_discard_equity(
+            &parse("AhAd5c"),
+            &parse("Kh9h5d"),
+            &[&opponent],
+            &Hand::new(),
+            DiscardTiming::AfterFlop,
+        );
+        // discard order: 5c, Ad, Ah
+        let expected = [
+            heads_up_equity("AhAd", "KsKd", "Kh9h5d", "5c"),
+            heads_up_equity("Ah5c", "KsKd", "Kh9h5d", "Ad"),
+            heads_up_equity("Ad5c", "KsKd", "Kh9h5d", "Ah"),
+        ];
+        for (x, y) in equity.iter().zip(&expected) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_before_flop() {
+        let opponent = [parse("QsQd")];
+        let equity = pineapple_discard_equity(
+            &parse("AhKh2c"),
+            &Hand::new(),
+            &[&opponent],
+            &Hand::new(),
+            DiscardTiming::BeforeFlop,
+        );
+        let expected = heads_up_equity("AhKh", "QsQd", "", "2c");
+        assert!((equity[0] - expected).abs() < 1e-12);
+        assert!(equity[0] > equity[1] && equity[0] > equity[2]);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let range = [parse("KsKd"), parse("9s9c"), parse("AhQc")];
+        let equity = pineapple_discard_equity(
+            &parse("AhAd5c"),
+            &parse("Kh9h5d"),
+            &[&range],
+            &Hand::new(),
+            DiscardTiming::AfterFlop,
+        );
+        // AhQc conflicts with the hole cards
+        let expected = (heads_up_equity("AhAd", "KsKd", "Kh9h5d", "5c")
+            + heads_up_equity("AhAd", "9s9c", "Kh9h5d", "5c"))
+            / 2.0;
+        assert!((equity[0] - expected).abs() < 1e-12);
+
+        // three-way pot with a range and a known hand
+        let opponent = [parse("QsQd")];
+        let equity = pineapple_discard_equity(
+            &parse("AhAd5c"),
+            &parse("Kh9h5d"),
+            &[&range, &opponent],
+            &Hand::new(),
+            DiscardTiming::AfterFlop,
+        );
+        assert!(equity[0] > 0.0 && equity[0] < 1.0);
+    }
+
+    #[test]
+    fn test_conflicting_ranges() {
+        // every holding of the range conflicts with the hole cards or the board
+        let range = [parse("AhKs"), parse("Kh9c")];
+        let equity = pineapple_discard_equity(
+            &parse("AhAd5c"),
+            &parse("Kh9h5d"),
+            &[&range],
+            &Hand::new(),
+            DiscardTiming::AfterFlop,
+        );
+        assert_eq!(equity, [0.0; 3]);
+
+        // the only holdings of two opponents conflict with each other
+        let (range1, range2) = ([parse("KsKd")], [parse("KcKd")]);
+        let equity = pineapple_discard_equity(
+            &parse("AhAd5c"),
+            &parse("Kh9h5d"),
+            &[&range1, &range2],
+            &Hand::new(),
+            DiscardTiming::AfterFlop,
+        );
+        assert_eq!(equity, [0.0; 3]);
+    }
+}
@@ -0,0 +1,56 @@
+/// Small pseudo-random number generator (xoshiro256**) used by Monte Carlo simulations and
+/// `Deck::shuffle()`. The same seed always yields the same sequence; not suitable for
+/// cryptographic purposes.
+///
+/// The sequence of a seed is part of the stable API: it only changes in a major version, so
+/// seeded simulations and shuffles are reproducible across minor updates.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Creates a new generator from `seed` (the state is expanded by SplitMix64).
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let mut state = [0; 4];
+        for s in &mut state {
+            x = x.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *s = z ^ (z >> 31);
+        }
+        Self { state }
+    }
+
+    /// Returns the next 64-bit random value.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Returns a uniformly distributed value in the range \[0, `n`).
+    #[inline]
+    pub fn gen_below(&mut self, n: usize) -> usize {
+        // Lemire's nearly divisionless method
+        let n = n as u64;
+        let mut m = (self.next_u64() as u128) * (n as u128);
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+            while (m as u64) < threshold {
+                m = (self.next_u64() as u128) * (n as u128);
+            }
+        }
+        (m >> 64) as usize
+    }
+}
@@ -0,0 +1,337 @@
+use crate::hand::*;
+use assets::constants::*;
+use std::cmp::Reverse;
+
+/// Rule for distributing odd chips of a pot that cannot be split evenly among tied winners.
+/// Each odd chip goes to one winner, in the order given by the rule.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OddChipRule {
+    /// Winners are ordered by seat, starting from the first seat left of the button.
+    LeftOfButton,
+    /// Winners are ordered by their highest hole card, compared by rank and then by suit
+    /// (spades > hearts > diamonds > clubs).
+    SuitOrder,
+}
+
+/// A player at showdown. Seats are the indices in the slice passed to `resolve_showdown()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ShowdownPlayer {
+    /// Hole cards (ignored when `folded` is `true`).
+    pub hole_cards: Hand,
+    /// Total number of chips put into the pot during the hand.
+    pub contribution: u64,
+    /// Whether the player has folded.
+    pub folded: bool,
+}
+
+/// A main pot or a side pot.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Pot {
+    /// Number of chips in the pot.
+    pub amount: u64,
+    /// Seats of the players that can win the pot, in ascending order.
+    pub eligible: Vec<usize>,
+    /// Seats of the winners and the chips they receive, in odd-chip order.
+    pub winners: Vec<(usize, u64)>,
+}
+
+/// Result of `resolve_showdown()`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Showdown {
+    /// The main pot followed by side pots in ascending order of contribution level.
+    pub pots: Vec<Pot>,
+    /// Number of chips each seat receives.
+    pub payouts: Vec<u64>,
+}
+
+/// Builds the main pot and side pots from the contributions of `players` and awards each pot
+/// to the best hands among its eligible players, i.e., the players who have not folded and
+/// have contributed at least the level of the pot.
+/// Hands are ranked by `(hole_cards + board).evaluate()`, so `hole_cards + board` of each
+/// player who has not folded must consist of 5-7 cards, and the cards must not be shared.
+/// Chips of folded players above the highest contribution of the remaining players are added
+/// to the last pot, and an uncalled bet forms a pot with a single eligible player.
+pub fn resolve_showdown(
+    players: &[ShowdownPlayer],
+    board: &Hand,
+    button: usize,
+    rule: OddChipRule,
+) -> Showdown {
+    assert!(button < players.len());
+    assert!(players.iter().any(|p| !p.folded));
+    let active = players.iter().filter(|p| !p.folded);
+    assert!(active
+        .clone()
+        .all(|p| 5 <= (p.hole_cards + *board).len() && (p.hole_cards + *board).len() <= 7));
+    let used = active.clone().fold(*board, |acc, p| acc + p.hole_cards);
+    assert_eq!(
+        used.len(),
+        board.len() + active.clone().map(|p| p.hole_cards.len()).sum::<usize>()
+    );
+
+    let ranks = players
+        .iter()
+        .map(|p| match p.folded {
+            true => 0,
+            false => (p.hole_cards + *board).evaluate(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut levels = active
+        .map(|p| p.contribution)
+        .filter(|&c| c > 0)
+        .collect::<Vec<_>>();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots = Vec::new();
+    let mut prev = 0;
+    for &level in &levels {
+        let amount = players
+            .iter()
+            .map(|p| p.contribution.min(level) - p.contribution.min(prev))
+            .sum();
+        let eligible = (0..players.len())
+            .filter(|&i| !players[i].folded && players[i].contribution >= level)
+            .collect();
+        pots.push((amount, eligible));
+        prev = level;
+    }
+    let dead_money = players
+        .iter()
+        .map(|p| p.contribution.saturating_sub(prev))
+        .sum::<u64>();
+    match pots.last_mut() {
+        Some(pot) => pot.0 += dead_money,
+        None => {
+            // only folded players have contributed: the remaining players share the chips
+            let eligible = (0..players.len()).filter(|&i| !players[i].folded);
+            pots.push((dead_money, eligible.collect()));
+        }
+    }
+
+    let mut payouts = vec![0; players.len()];
+    let pots = pots
+        .into_iter()
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, eligible): (u64, Vec<usize>)| {
+            let best = eligible.iter().map(|&i| ranks[i]).max().unwrap();
+            let mut winners = eligible
+                .iter()
+                .copied()
+                .filter(|&i| ranks[i] == best)
+                .collect::<Vec<_>>();
+            match rule {
+                OddChipRule::LeftOfButton => winners
+                    .sort_unstable_by_key(|&i| (i + players.len() - button - 1) % players.len()),
+                OddChipRule::SuitOrder => {
+                    winners.sort_unstable_by_key(|&i| Reverse(highest_card(&players[i].hole_cards)))
+                }
+            }
+            let share = amount / winners.len() as u64;
+            let odd_chips = (amount % winners.len() as u64) as usize;
+            let winners = winners
+                .into_iter()
+                .enumerate()
+                .map(|(k, i)| {
+                    let chips = share + (k < odd_chips) as u64;
+                    payouts[i] += chips;
+                    (i, chips)
+                })
+                .collect();
+            Pot {
+                amount,
+                eligible,
+                winners,
+            }
+        })
+        .collect();
+
+    Showdown { pots, payouts }
+}
+
+/// Returns the card ID of the highest card of `hand` (card IDs are ordered by rank and then by
+/// suit), or `None` if `hand` is empty.
+fn highest_card(hand: &Hand) -> Option<usize> {
+    (0..NUMBER_OF_CARDS).rev().find(|&card| hand.contains(card))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn player(hole_cards: &str, contribution: u64) -> ShowdownPlayer {
+        ShowdownPlayer {
+            hole_cards: hole_cards.parse().unwrap(),
+            contribution,
+            folded: false,
+        }
+    }
+
+    fn folded(contribution: u64) -> ShowdownPlayer {
+        ShowdownPlayer {
+            hole_cards: Hand::new(),
+            contribution,
+            folded: true,
+        }
+    }
+
+    #[test]
+    fn test_single_winner() {
+        let board = "2c7d9hJsKc".parse().unwrap();
+        let players = [player("AsAh", 100), player("QsQh", 100), folded(30)];
+        let result = resolve_showdown(&players, &board, 0, OddChipRule::LeftOfButton);
+        assert_eq!(
+            result.pots,
+            vec![Pot {
+                amount: 230,
+                eligible: vec![0, 1],
+                winners: vec![(0, 230)],
+            }]
+        );
+        assert_eq!(result.payouts, vec![230, 0, 0]);
+    }
+
+    #[test]
+    fn test_side_pots() {
+        // seat 0 is all-in for 50 and has the best hand; seat 1 beats seat 2 for the side pots
+        let board = "2c7d9hJsKc".parse().unwrap();
+        let players = [
+            player("KsKh", 50),
+            player("JdJh", 200),
+            player("AsAh", 120),
+            player("9s9c", 200),
+            folded(70),
+        ];
+        let result = resolve_showdown(&players, &board, 4, OddChipRule::LeftOfButton);
+        let summary = result
+            .pots
+            .iter()
+            .map(|pot| (pot.amount, pot.eligible.clone(), pot.winners.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (250, vec![0, 1, 2, 3], vec![(0, 250)]),
+                (70 * 3 + 20, vec![1, 2, 3], vec![(1, 230)]),
+                (80 * 2, vec![1, 3], vec![(1, 160)]),
+            ]
+        );
+        assert_eq!(result.payouts, vec![250, 390, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_uncalled_bet_and_dead_money() {
+        let board = "2c7d9hJsKc".parse().unwrap();
+        let players = [player("3s4s", 300), player("AsAh", 100), folded(150)];
+        let result = resolve_showdown(&players, &board, 1, OddChipRule::LeftOfButton);
+        assert_eq!(result.pots.len(), 2);
+        assert_eq!(result.pots[0].winners, vec![(1, 300)]);
+        assert_eq!(result.pots[1].eligible, vec![0]);
+        assert_eq!(result.pots[1].winners, vec![(0, 250)]);
+
+        // everyone else folded without a showdown contribution
+        let players = [player("3s4s", 0), folded(20), folded(10)];
+        let result = resolve_showdown(&players, &board, 0, OddChipRule::LeftOfButton);
+        assert_eq!(result.payouts, vec![30, 0, 0]);
+    }
+
+    #[test]
+    fn test_odd_chips() {
+        // the board plays: 3-way split of 100 chips
+        let board = "AcKcQcJcTc".parse().unwrap();
+        let players = [
+            player("2s3h", 25),
+            player("4d5s", 25),
+            folded(25),
+            player("6c7h", 25),
+        ];
+
+        let result = resolve_showdown(&players, &board, 0, OddChipRule::LeftOfButton);
+        assert_eq!(result.pots[0].winners, vec![(1, 34), (3, 33), (0, 33)]);
+        let result = resolve_showdown(&players, &board, 1, OddChipRule::LeftOfButton);
+        assert_eq!(result.pots[0].winners, vec![(3, 34), (0, 33), (1, 33)]);
+        let result = resolve_showdown(&players, &board, 3, OddChipRule::LeftOfButton);
+        assert_eq!(result.payouts, vec![34, 33, 0, 33]);
+
+        // highest hole cards: 7h (seat 3) > 5s (seat 1) > 3h (seat 0)
+        let result = resolve_showdown(&players, &board, 0, OddChipRule::SuitOrder);
+        assert_eq!(result.pots[0].winners, vec![(3, 34), (1, 33), (0, 33)]);
+
+        // same rank: suit decides (spades > hearts)
+        let players = [player("2d7h", 1), player("3c7s", 1), folded(1)];
+        let result = resolve_showdown(&players, &board, 1, OddChipRule::SuitOrder);
+        assert_eq!(result.payouts, vec![1, 2, 0]);
+        let players = [player("2d7h", 3), player("3c7s", 2)];
+        let result = resolve_showdown(&players, &board, 1, OddChipRule::SuitOrder);
+        assert_eq!(result.pots[0].winners, vec![(1, 2), (0, 2)]);
+        assert_eq!(result.payouts, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_random_showdowns() {
+        let mut rng = Rng::new(0);
+        for _ in 0..10000 {
+            let num_players = 2 + rng.gen_below(8);
+            let mut used = Hand::new();
+            let mut deal = |rng: &mut Rng, num_cards: usize| {
+                let mut hand = Hand::new();
+                while hand.len() < num_cards {
+                    let card = rng.gen_below(52);
+                    if !used.contains(card) {
+                        used = used.add_card(card);
+                        hand = hand.add_card(card);
+                    }
+                }
+                hand
+            };
+            let board = deal(&mut rng, 5);
+            let mut players = (0..num_players)
+                .map(|_| ShowdownPlayer {
+                    hole_cards: deal(&mut rng, 2),
+                    contribution: 10 * rng.gen_below(4) as u64 + rng.gen_below(3) as u64,
+                    folded: rng.gen_below(3) == 0,
+                })
+                .collect::<Vec<_>>();
+            players[rng.gen_below(num_players)].folded = false;
+            let button = rng.gen_below(num_players);
+            let rule = match rng.gen_below(2) {
+                0 => OddChipRule::LeftOfButton,
+                _ => OddChipRule::SuitOrder,
+            };
+
+            let result = resolve_showdown(&players, &board, button, rule);
+            let total = players.iter().map(|p| p.contribution).sum::<u64>();
+            assert_eq!(result.payouts.iter().sum::<u64>(), total);
+            assert_eq!(result.pots.iter().map(|p| p.amount).sum::<u64>(), total);
+            for pot in &result.pots {
+                let best = pot
+                    .eligible
+                    .iter()
+                    .map(|&i| (players[i].hole_cards + board).evaluate())
+                    .max()
+                    .unwrap();
+                let (min, max) = (pot.winners.last().unwrap().1, pot.winners[0].1);
+                assert!(max - min <= 1);
+                for &(i, _) in &pot.winners {
+                    assert!(!players[i].folded);
+                    assert_eq!((players[i].hole_cards + board).evaluate(), best);
+                }
+            }
+            for (i, &payout) in result.payouts.iter().enumerate() {
+                if players[i].folded {
+                    assert_eq!(payout, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_shared_cards() {
+        let board = "2c7d9hJsKc".parse().unwrap();
+        let players = [player("AsAh", 10), player("AsKh", 10)];
+        resolve_showdown(&players, &board, 0, OddChipRule::LeftOfButton);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::hand::*;
+
+/// Evaluates eight hands at once and returns the hand strengths (same result as
+/// `Hand::evaluate()`). Every element of `hands` must consist of 5-7 cards.
+/// On x86_64 processors with AVX2, the flush test and the table lookups are performed in
+/// 256-bit registers with gather instructions; otherwise this falls back to scalar code.
+#[inline]
+pub fn evaluate_x8(hands: &[Hand; 8]) -> [u16; 8] {
+    #[cfg(all(target_arch = "x86_64", feature = "std"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { avx2::evaluate_x8(hands) };
+        }
+        evaluate_x8_scalar(hands)
+    }
+    // without `std`, AVX2 is used only when it is enabled at compile time
+    #[cfg(all(target_arch = "x86_64", not(feature = "std"), target_feature = "avx2"))]
+    {
+        unsafe { avx2::evaluate_x8(hands) }
+    }
+    #[cfg(not(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2"))))]
+    {
+        evaluate_x8_scalar(hands)
+    }
+}
+
+#[cfg_attr(
+    all(target_arch = "x86_64", not(feature = "std"), target_feature = "avx2"),
+    allow(dead_code)
+)]
+#[inline]
+fn evaluate_x8_scalar(hands: &[Hand; 8]) -> [u16; 8] {
+    let mut result = [0; 8];
+    for (r, hand) in result.iter_mut().zip(hands.iter()) {
+        *r = hand.evaluate();
+    }
+    result
+}
+
+#[cfg(all(target_arch = "x86_64", any(feature = "std", target_feature = "avx2")))]
+mod avx2 {
+    use crate::hand::*;
+    use crate::tables::{LOOKUP, LOOKUP_FLUSH, OFFSETS};
+    use assets::constants::*;
+    use core::arch::x86_64::*;
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn evaluate_x8(hands: &[Hand; 8]) -> [u16; 8] {
+        let mut key_lo = [0u32; 8];
+        let mut key_hi = [0u32; 8];
+        let mut mask_lo = [0u32; 8];
+        let mut mask_hi = [0u32; 8];
+        for (i, hand) in hands.iter().enumerate() {
+            key_lo[i] = hand.get_key() as u32;
+            key_hi[i] = (hand.get_key() >> 32) as u32;
+            mask_lo[i] = hand.get_mask() as u32;
+            mask_hi[i] = (hand.get_mask() >> 32) as u32;
+        }
+        let key_lo = _mm256_loadu_si256(key_lo.as_ptr() as *const __m256i);
+        let key_hi = _mm256_loadu_si256(key_hi.as_ptr() as *const __m256i);
+        let mask_lo = _mm256_loadu_si256(mask_lo.as_ptr() as *const __m256i);
+        let mask_hi = _mm256_loadu_si256(mask_hi.as_ptr() as *const __m256i);
+        let low16 = _mm256_set1_epi32(0xffff);
+
+        // flush test: select the rank bits of the suit whose counter has overflowed
+        let flush_bit = |suit: usize| {
+            let bit = ((FLUSH_MASK >> 32) as u32 & (0x8000_0000 >> (4 * suit))) as i32;
+            let bit = _mm256_set1_epi32(bit);
+            _mm256_cmpeq_epi32(_mm256_and_si256(key_hi, bit), bit)
+        };
+        let (club, diamond, heart, spade) =
+            (flush_bit(0), flush_bit(1), flush_bit(2), flush_bit(3));
+        let is_flush = _mm256_or_si256(
+            _mm256_or_si256(club, diamond),
+            _mm256_or_si256(heart, spade),
+        );
+        let flush_key = _mm256_or_si256(
+            _mm256_or_si256(
+                _mm256_and_si256(club, _mm256_and_si256(mask_lo, low16)),
+                _mm256_and_si256(diamond, _mm256_srli_epi32(mask_lo, 16)),
+            ),
+            _mm256_or_si256(
+                _mm256_and_si256(heart, _mm256_and_si256(mask_hi, low16)),
+                _mm256_and_si256(spade, _mm256_srli_epi32(mask_hi, 16)),
+            ),
+        );
+
+        // non-flush: hash the rank key with the offset table
+        let offset = _mm256_i32gather_epi32::<4>(
+            OFFSETS.as_ptr(),
+            _mm256_srli_epi32(key_lo, OFFSET_SHIFT as i32),
+        );
+        let hash_key = _mm256_add_epi32(key_lo, offset);
+        // `vpgatherdd` loads 4 bytes for each 16-bit entry, which the padding entry at the end
+        // of each table keeps in bounds
+        let (lookup, lookup_flush): (&[u16], &[u16]) = (&LOOKUP, &LOOKUP_FLUSH);
+        let rank = _mm256_i32gather_epi32::<2>(lookup.as_ptr() as *const i32, hash_key);
+        let flush_rank =
+            _mm256_i32gather_epi32::<2>(lookup_flush.as_ptr() as *const i32, flush_key);
+        let result = _mm256_and_si256(_mm256_blendv_epi8(rank, flush_rank, is_flush), low16);
+
+        let mut tmp = [0u32; 8];
+        _mm256_storeu_si256(tmp.as_mut_ptr() as *mut __m256i, result);
+        let mut ranks = [0; 8];
+        for (r, t) in ranks.iter_mut().zip(tmp.iter()) {
+            *r = *t as u16;
+        }
+        ranks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assets::constants::*;
+
+    fn check(hands: &[Hand; 8]) {
+        assert_eq!(evaluate_x8(hands), evaluate_x8_scalar(hands));
+    }
+
+    #[test]
+    fn test_all_7card_combinations() {
+        let mut hands = [Hand::new(); 8];
+        let mut num_hands = 0;
+        for i in 0..(NUMBER_OF_CARDS - 6) {
+            let hand = Hand::new().add_card(i);
+            for j in (i + 1)..(NUMBER_OF_CARDS - 5) {
+                let hand = hand.add_card(j);
+                for k in (j + 1)..(NUMBER_OF_CARDS - 4) {
+                    let hand = hand.add_card(k);
+                    for m in (k + 1)..(NUMBER_OF_CARDS - 3) {
+                        let hand = hand.add_card(m);
+                        for n in (m + 1)..(NUMBER_OF_CARDS - 2) {
+                            let hand = hand.add_card(n);
+                            for p in (n + 1)..(NUMBER_OF_CARDS - 1) {
+                                let hand = hand.add_card(p);
+                                for q in (p + 1)..NUMBER_OF_CARDS {
+                                    hands[num_hands % 8] = hand.add_card(q);
+                                    num_hands += 1;
+                                    if num_hands % 8 == 0 {
+                                        check(&hands);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        // 133,784,560 is a multiple of 8
+        assert_eq!(num_hands, 133784560);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_5_6cards() {
+        let hands = [
+            "AsKsQsJsTs",
+            "2c3d4h5s7c",
+            "AhAdAcKsKh",
+            "2h3h4h5h7h9c",
+            "7c7d8h8s9c9d",
+            "AcKdQhJs9c",
+            "2d2h",
+            "TdJdQdKdAd9d",
+        ];
+        let mut x8 = [Hand::new(); 8];
+        for (h, s) in x8.iter_mut().zip(hands.iter()) {
+            *h = s.parse().unwrap();
+        }
+        x8[6] = x8[6].add_card(20).add_card(33).add_card(50);
+        check(&x8);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::deck::*;
+use crate::hand::*;
+use crate::low::*;
+use crate::rng::Rng;
+use assets::constants::*;
+
+/// Maximum number of players in a seven-card stud game.
+pub const MAX_STUD_PLAYERS: usize = 8;
+
+/// Variants of seven-card stud.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StudVariant {
+    /// The best high hand wins the whole pot.
+    High,
+    /// The pot is split between the best high hand and the best eight-or-better low hand.
+    /// (when no one has a qualifying low, the best high hand scoops)
+    HighLowEightOrBetter,
+}
+
+/// Computes seven-card stud equity by enumerating all possible deals of the unknown cards.
+/// `hands` contains the known cards (hole cards and up-cards) of each player, and `dead_cards`
+/// contains the cards that can no longer be dealt (e.g., up-cards of folded players).
+/// Return value: the expected share of the pot for each player
+///
+/// The number of deals grows very quickly; use `stud_equity_monte_carlo()` on early streets.
+pub fn stud_equity(hands: &[Hand], dead_cards: &Hand, variant: StudVariant) -> Vec<f64> {
+    let alive_cards = check_stud_input(hands, dead_cards);
+    let mut players = [Hand::new(); MAX_STUD_PLAYERS];
+    players[..hands.len()].copy_from_slice(hands);
+    let mut equity = vec![0.0; hands.len()];
+    let mut count = 0u64;
+    stud_enumerate(
+        &mut players[..hands.len()],
+        0,
+        0,
+        &alive_cards,
+        0,
+        variant,
+        &mut equity,
+        &mut count,
+    );
+    for e in &mut equity {
+        *e /= count as f64;
+    }
+    equity
+}
+
+/// Estimates seven-card stud equity by sampling `num_trials` random deals of the unknown cards.
+/// The arguments are the same as `stud_equity()`, and `seed` initializes the random generator.
+/// Return value: the estimated share of the pot for each player
+pub fn stud_equity_monte_carlo(
+    hands: &[Hand],
+    dead_cards: &Hand,
+    variant: StudVariant,
+    num_trials: u32,
+    seed: u64,
+) -> Vec<f64> {
+    assert!(num_trials > 0);
+    check_stud_input(hands, dead_cards);
+    let known_cards = hands.iter().fold(*dead_cards, |acc, hand| acc + *hand);
+    let base_deck = Deck::without(&known_cards);
+    let num_needed = hands.iter().map(|hand| 7 - hand.len()).sum::<usize>();
+    let mut rng = Rng::new(seed);
+    let mut players = [Hand::new(); MAX_STUD_PLAYERS];
+    let mut equity = vec![0.0; hands.len()];
+    for _ in 0..num_trials {
+        let mut deck = base_deck;
+        deck.shuffle_top(num_needed, &mut rng);
+        for (player, hand) in players.iter_mut().zip(hands) {
+            *player = *hand + deck.deal(7 - hand.len());
+        }
+        stud_showdown(&players[..hands.len()], variant, &mut equity);
+    }
+    for e in &mut equity {
+        *e /= num_trials as f64;
+    }
+    equity
+}
+
+fn check_stud_input(hands: &[Hand], dead_cards: &Hand) -> Vec<usize> {
+    assert!(2 <= hands.len() && hands.len() <= MAX_STUD_PLAYERS);
+    assert!(hands.iter().all(|hand| hand.len() <= 7));
+    let mut mask = dead_cards.get_mask();
+    let mut num_cards = dead_cards.len();
+    for hand in hands {
+        mask |= hand.get_mask();
+        num_cards += hand.len();
+    }
+    assert_eq!(mask.count_ones() as usize, num_cards);
+    let (alive_cards, num_alive_cards) = compute_alive_cards(mask);
+    let num_needed = hands.iter().map(|hand| 7 - hand.len()).sum::<usize>();
+    assert!(num_alive_cards >= num_needed);
+    alive_cards[..num_alive_cards].to_vec()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn stud_enumerate(
+    players: &mut [Hand],
+    idx: usize,
+    start: usize,
+    alive_cards: &[usize],
+    used_mask: u64,
+    variant: StudVariant,
+    equity: &mut [f64],
+    count: &mut u64,
+) {
+    if idx == players.len() {
+        stud_showdown(players, variant, equity);
+        *count += 1;
+        return;
+    }
+    if players[idx].len() == 7 {
+        stud_enumerate(
+            players,
+            idx + 1,
+            0,
+            alive_cards,
+            used_mask,
+            variant,
+            equity,
+            count,
+        );
+        return;
+    }
+    let hand = players[idx];
+    for i in start..alive_cards.len() {
+        let card = alive_cards[i];
+        let card_mask = CARDS[card].1;
+        if (used_mask & card_mask) != 0 {
+            continue;
+        }
+        players[idx] = hand.add_card(card);
+        stud_enumerate(
+            players,
+            idx,
+            i + 1,
+            alive_cards,
+            used_mask | card_mask,
+            variant,
+            equity,
+            count,
+        );
+    }
+    players[idx] = hand;
+}
+
+fn stud_showdown(players: &[Hand], variant: StudVariant, equity: &mut [f64]) {
+    let mut ranks = [0; MAX_STUD_PLAYERS];
+    for (rank, player) in ranks.iter_mut().zip(players) {
+        *rank = player.evaluate();
+    }
+    let ranks = &ranks[..players.len()];
+
+    let high_share = match variant {
+        StudVariant::High => 1.0,
+        StudVariant::HighLowEightOrBetter => {
+            let mut lows = [0; MAX_STUD_PLAYERS];
+            for (low, player) in lows.iter_mut().zip(players) {
+                *low = evaluate_low_8_or_better(player);
+            }
+            let lows = &lows[..players.len()];
+            if lows.iter().any(|&low| low > 0) {
+                award(lows, 0.5, equity);
+                0.5
+            } else {
+                1.0
+            }
+        }
+    };
+    award(ranks, high_share, equity);
+}
+
+fn award(ranks: &[u16], share: f64, equity: &mut [f64]) {
+    let best = *ranks.iter().max().unwrap();
+    let num_winners = ranks.iter().filter(|&&rank| rank == best).count();
+    let share = share / num_winners as f64;
+    for (e, &rank) in equity.iter_mut().zip(ranks) {
+        if rank == best {
+            *e += share;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(hand_str: &str) -> Hand {
+        hand_str.parse::<Hand>().unwrap()
+    }
+
+    #[test]
+    fn test_stud_complete_hands() {
+        let hands = [parse("AcAdKhKs2c3d4h"), parse("QcQdJhJs5c6d8h")];
+        let equity = stud_equity(&hands, &Hand::new(), StudVariant::High);
+        assert_eq!(equity, vec![1.0, 0.0]);
+
+        // player 1 scoops when no one has a qualifying low
+        let hands = [parse("AcAdKhKs9c3d4h"), parse("QcQd2hJs5c6d8h")];
+        let equity = stud_equity(&hands, &Hand::new(), StudVariant::HighLowEightOrBetter);
+        assert_eq!(equity, vec![1.0, 0.0]);
+
+        // player 2 takes the low half with 8-7-6-5-2
+        let hands = [parse("AcAdKhKs9c3d4h"), parse("QcQd2h7s5c6d8h")];
+        let equity = stud_equity(&hands, &Hand::new(), StudVariant::HighLowEightOrBetter);
+        assert_eq!(equity, vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_stud_seventh_street() {
+        let hands = [parse("As2s5s9sQdJc"), parse("KcKdKhTc7d8c")];
+        let dead_cards = parse("3s4s");
+        let equity = stud_equity(&hands, &dead_cards, StudVariant::High);
+
+        let mask = hands[0].get_mask() | hands[1].get_mask() | dead_cards.get_mask();
+        let (alive_cards, num_alive_cards) = compute_alive_cards(mask);
+        let alive_cards = &alive_cards[..num_alive_cards];
+        let mut wins = [0.0; 2];
+        let mut count = 0.0;
+        for &c1 in alive_cards {
+            for &c2 in alive_cards {
+                if c1 != c2 {
+                    let rank1 = hands[0].add_card(c1).evaluate();
+                    let rank2 = hands[1].add_card(c2).evaluate();
+                    if rank1 >= rank2 {
+                        wins[0] += if rank1 == rank2 { 0.5 } else { 1.0 };
+                    }
+                    if rank1 <= rank2 {
+                        wins[1] += if rank1 == rank2 { 0.5 } else { 1.0 };
+                    }
+                    count += 1.0;
+                }
+            }
+        }
+        assert!((equity[0] - wins[0] / count).abs() < 1e-9);
+        assert!((equity[1] - wins[1] / count).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stud_monte_carlo() {
+        let hands = [
+            parse("AcKcQcTs5s3h"),
+            parse("7h7d7s8s6c2c"),
+            parse("2d3d4d9dKsJd"),
+        ];
+        let dead_cards = parse("9h");
+        for &variant in &[StudVariant::High, StudVariant::HighLowEightOrBetter] {
+            let exact = stud_equity(&hands, &dead_cards, variant);
+            let approx = stud_equity_monte_carlo(&hands, &dead_cards, variant, 100000, 1);
+            assert!((exact.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!((approx.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            for (x, y) in exact.iter().zip(&approx) {
+                assert!((x - y).abs() < 0.01);
+            }
+        }
+    }
+}
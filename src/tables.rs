@@ -0,0 +1,85 @@
+// lookup tables used by the evaluators.
+//
+// by default, the tables are the constants generated by the scripts in `assets`. with the
+// `generate-tables` feature, they are generated from `assets::constants` by the build script.
+// with the `runtime-tables` feature, they are built on first use by the generator in `scripts`
+// (first-fit decreasing offset table and perfect hashing).
+
+#[cfg(not(any(feature = "runtime-tables", feature = "generate-tables")))]
+pub(crate) use assets::lookup::{LOOKUP, LOOKUP_FLUSH};
+#[cfg(not(any(feature = "runtime-tables", feature = "generate-tables")))]
+pub(crate) use assets::offsets::OFFSETS;
+
+#[cfg(all(feature = "generate-tables", not(feature = "runtime-tables")))]
+#[allow(clippy::large_const_arrays)]
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/tables.rs"));
+}
+#[cfg(all(feature = "generate-tables", not(feature = "runtime-tables")))]
+pub(crate) use generated::{LOOKUP, LOOKUP_FLUSH, OFFSETS};
+
+#[cfg(feature = "runtime-tables")]
+pub(crate) use runtime::{LOOKUP, LOOKUP_FLUSH, OFFSETS};
+
+#[cfg(feature = "runtime-tables")]
+mod runtime {
+    use std::ops::Deref;
+    use std::sync::OnceLock;
+
+    pub(crate) struct Tables {
+        lookup: Vec<u16>,
+        lookup_flush: Vec<u16>,
+        offsets: Vec<i32>,
+    }
+
+    /// Table that is built on first dereference.
+    pub(crate) struct RuntimeTable<T: 'static>(fn(&'static Tables) -> &'static Vec<T>);
+
+    impl<T> Deref for RuntimeTable<T> {
+        type Target = [T];
+        #[inline]
+        fn deref(&self) -> &[T] {
+            (self.0)(tables())
+        }
+    }
+
+    pub(crate) static LOOKUP: RuntimeTable<u16> = RuntimeTable(|t| &t.lookup);
+    pub(crate) static LOOKUP_FLUSH: RuntimeTable<u16> = RuntimeTable(|t| &t.lookup_flush);
+    pub(crate) static OFFSETS: RuntimeTable<i32> = RuntimeTable(|t| &t.offsets);
+
+    #[inline]
+    fn tables() -> &'static Tables {
+        static TABLES: OnceLock<Tables> = OnceLock::new();
+        TABLES.get_or_init(|| {
+            let (offsets, _) = scripts::generate_offsets(&scripts::rank_keys());
+            let (lookup, lookup_flush) = scripts::generate_lookup_tables(&offsets)
+                .expect("generated offsets must give a perfect hash function");
+            Tables {
+                lookup,
+                lookup_flush,
+                offsets,
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "runtime-tables"))]
+mod tests {
+    use super::*;
+    use assets::constants::OFFSET_SHIFT;
+
+    #[test]
+    fn test_runtime_tables() {
+        // the committed offsets come from a tighter packing, but every rank key must be mapped
+        // to the same hand rank
+        let hash = |offsets: &[i32], key: u64| {
+            let key = key as usize;
+            key.wrapping_add(offsets[key >> OFFSET_SHIFT] as usize)
+        };
+        for key in scripts::rank_keys() {
+            let expected = assets::lookup::LOOKUP[hash(&assets::offsets::OFFSETS, key)];
+            assert_eq!(LOOKUP[hash(&OFFSETS, key)], expected);
+        }
+        assert_eq!(LOOKUP_FLUSH[..], assets::lookup::LOOKUP_FLUSH[..]);
+    }
+}
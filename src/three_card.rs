@@ -0,0 +1,214 @@
+use crate::hand::*;
+use assets::constants::*;
+use std::collections::HashMap;
+
+/// Number of three card poker hand categories.
+pub const NUM_THREE_CARD_CATEGORIES: usize = ThreeCardCategory::StraightFlush as usize + 1;
+
+/// Standard Pairs Plus pay table (indexed by `ThreeCardCategory`; 0 means the bet loses).
+pub const PAIRS_PLUS_PAY_TABLE: [u32; NUM_THREE_CARD_CATEGORIES] = [0, 1, 4, 6, 30, 40];
+
+/// Standard ante bonus pay table (indexed by `ThreeCardCategory`).
+pub const ANTE_BONUS_PAY_TABLE: [u32; NUM_THREE_CARD_CATEGORIES] = [0, 0, 0, 1, 4, 5];
+
+/// Hand categories of three card poker. (straights beat flushes)
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ThreeCardCategory {
+    HighCard = 0,
+    Pair = 1,
+    Flush = 2,
+    Straight = 3,
+    ThreeOfAKind = 4,
+    StraightFlush = 5,
+}
+
+/// Returns the hand category from hand rank computed by `evaluate_three_card()`.
+#[inline]
+pub fn get_three_card_category(hand_rank: u16) -> ThreeCardCategory {
+    match hand_rank >> 13 {
+        0 => ThreeCardCategory::HighCard,
+        1 => ThreeCardCategory::Pair,
+        2 => ThreeCardCategory::Flush,
+        3 => ThreeCardCategory::Straight,
+        4 => ThreeCardCategory::ThreeOfAKind,
+        5 => ThreeCardCategory::StraightFlush,
+        _ => unreachable!(),
+    }
+}
+
+/// Returns three card poker hand strength in 16-bit integer (stronger hand yields higher value).
+/// `hand.len()` must be 3; A-2-3 is the lowest straight.
+#[inline]
+pub fn evaluate_three_card(hand: &Hand) -> u16 {
+    let mask = hand.get_mask();
+    let suits = [
+        mask as u16,
+        (mask >> 16) as u16,
+        (mask >> 32) as u16,
+        (mask >> 48) as u16,
+    ];
+    let rankset = suits[0] | suits[1] | suits[2] | suits[3];
+    let is_flush = suits.iter().any(|&s| s.count_ones() == 3);
+
+    match rankset.count_ones() {
+        1 => ((ThreeCardCategory::ThreeOfAKind as u16) << 13) | rankset.trailing_zeros() as u16,
+        2 => {
+            let pairset = (suits[0] & (suits[1] | suits[2] | suits[3]))
+                | (suits[1] & (suits[2] | suits[3]))
+                | (suits[2] & suits[3]);
+            let pair = pairset.trailing_zeros() as u16;
+            let kicker = (rankset ^ pairset).trailing_zeros() as u16;
+            ((ThreeCardCategory::Pair as u16) << 13) | (pair << 4) | kicker
+        }
+        _ => {
+            let straight_top = match rankset {
+                0b1_0000_0000_0011 => Some(1),
+                _ if rankset >> rankset.trailing_zeros() == 0b111 => {
+                    Some(15 - rankset.leading_zeros() as u16)
+                }
+                _ => None,
+            };
+            match (straight_top, is_flush) {
+                (Some(top), true) => ((ThreeCardCategory::StraightFlush as u16) << 13) | top,
+                (Some(top), false) => ((ThreeCardCategory::Straight as u16) << 13) | top,
+                (None, true) => ((ThreeCardCategory::Flush as u16) << 13) | rankset,
+                (None, false) => rankset,
+            }
+        }
+    }
+}
+
+/// Returns whether the dealer's hand qualifies (queen high or better) in the Ante/Play game.
+#[inline]
+pub fn three_card_dealer_qualifies(hand_rank: u16) -> bool {
+    hand_rank >= 1 << 10
+}
+
+/// Computes the exact house edge of the Pairs Plus bet by enumerating all 22,100 hands.
+/// `pay_table` is indexed by `ThreeCardCategory`, and the bet loses when the payout is 0.
+pub fn pairs_plus_house_edge(pay_table: &[u32; NUM_THREE_CARD_CATEGORIES]) -> f64 {
+    let mut total = 0i64;
+    let mut count = 0i64;
+    for i in 0..(NUMBER_OF_CARDS - 2) {
+        let hand = Hand::new().add_card(i);
+        for j in (i + 1)..(NUMBER_OF_CARDS - 1) {
+            let hand = hand.add_card(j);
+            for k in (j + 1)..NUMBER_OF_CARDS {
+                let hand = hand.add_card(k);
+                let category = get_three_card_category(evaluate_three_card(&hand));
+                total += match pay_table[category as usize] {
+                    0 => -1,
+                    payout => payout as i64,
+                };
+                count += 1;
+            }
+        }
+    }
+    -total as f64 / count as f64
+}
+
+/// Computes the exact house edge of the Ante/Play game per ante, assuming the player follows the
+/// optimal strategy. All 22,100 player hands are enumerated against all 18,424 dealer hands.
+/// `ante_bonus` is indexed by `ThreeCardCategory` and is paid whenever the player plays.
+pub fn ante_play_house_edge(ante_bonus: &[u32; NUM_THREE_CARD_CATEGORIES]) -> f64 {
+    let mut hands = Vec::with_capacity(22100);
+    let mut classes = HashMap::new();
+    for i in 0..(NUMBER_OF_CARDS - 2) {
+        let hand = Hand::new().add_card(i);
+        for j in (i + 1)..(NUMBER_OF_CARDS - 1) {
+            let hand = hand.add_card(j);
+            for k in (j + 1)..NUMBER_OF_CARDS {
+                let hand = hand.add_card(k);
+                hands.push((hand.get_mask(), evaluate_three_card(&hand)));
+                let entry = classes.entry(canonical_mask(hand.get_mask()));
+                entry.or_insert((hand, 0)).1 += 1;
+            }
+        }
+    }
+
+    let mut total = 0i64;
+    for (hand, weight) in classes.values() {
+        let mask = hand.get_mask();
+        let rank = evaluate_three_card(hand);
+        let mut play = 0i64;
+        let mut count = 0i64;
+        for &(dealer_mask, dealer_rank) in &hands {
+            if (mask & dealer_mask) != 0 {
+                continue;
+            }
+            play += if !three_card_dealer_qualifies(dealer_rank) {
+                1
+            } else if rank > dealer_rank {
+                2
+            } else if rank < dealer_rank {
+                -2
+            } else {
+                0
+            };
+            count += 1;
+        }
+        play += ante_bonus[get_three_card_category(rank) as usize] as i64 * count;
+        total += weight * play.max(-count);
+    }
+
+    let num_dealer_hands = 18424;
+    -total as f64 / (hands.len() * num_dealer_hands) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate_three_card_str(hand_str: &str) -> u16 {
+        evaluate_three_card(&hand_str.parse::<Hand>().unwrap())
+    }
+
+    #[test]
+    fn test_all_3card_combinations() {
+        let mut counter = [0; NUM_THREE_CARD_CATEGORIES];
+        for i in 0..(NUMBER_OF_CARDS - 2) {
+            let hand = Hand::new().add_card(i);
+            for j in (i + 1)..(NUMBER_OF_CARDS - 1) {
+                let hand = hand.add_card(j);
+                for k in (j + 1)..NUMBER_OF_CARDS {
+                    let hand = hand.add_card(k);
+                    counter[get_three_card_category(evaluate_three_card(&hand)) as usize] += 1;
+                }
+            }
+        }
+        assert_eq!(counter, [16440, 3744, 1096, 720, 52, 48]);
+    }
+
+    #[test]
+    fn test_three_card_edge_cases() {
+        assert!(evaluate_three_card_str("AsKsQs") > evaluate_three_card_str("3c2cAc"));
+        assert!(evaluate_three_card_str("3c2cAc") > evaluate_three_card_str("AdAhAs"));
+        assert!(evaluate_three_card_str("2d2h2s") > evaluate_three_card_str("AdKhQs"));
+        assert!(evaluate_three_card_str("3d2hAs") > evaluate_three_card_str("AhKh9h"));
+        assert!(evaluate_three_card_str("5h3h2h") > evaluate_three_card_str("AcAdKh"));
+        assert!(evaluate_three_card_str("2c2d3h") > evaluate_three_card_str("AcKdJh"));
+        assert!(evaluate_three_card_str("3c3dAh") > evaluate_three_card_str("2c2dAd"));
+        assert!(evaluate_three_card_str("3c3d4h") > evaluate_three_card_str("3h3s2d"));
+        assert_eq!(
+            evaluate_three_card_str("AcKdJh"),
+            evaluate_three_card_str("AdKhJs")
+        );
+        assert!(three_card_dealer_qualifies(evaluate_three_card_str(
+            "Qc3d2h"
+        )));
+        assert!(!three_card_dealer_qualifies(evaluate_three_card_str(
+            "JcTd8h"
+        )));
+    }
+
+    #[test]
+    fn test_house_edge() {
+        // published values: 2.32% (1-4-6-30-40), 7.28% (1-3-6-30-40) and 3.37% (Ante/Play)
+        let house_edge = pairs_plus_house_edge(&PAIRS_PLUS_PAY_TABLE);
+        assert!((house_edge - 512.0 / 22100.0).abs() < 1e-12);
+        let house_edge = pairs_plus_house_edge(&[0, 1, 3, 6, 30, 40]);
+        assert!((house_edge - 1608.0 / 22100.0).abs() < 1e-12);
+        let house_edge = ante_play_house_edge(&ANTE_BONUS_PAY_TABLE);
+        assert!((house_edge - 0.033730).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::hand::*;
+use crate::wild::*;
+use assets::constants::*;
+use std::collections::HashMap;
+
+/// Payouts of a video poker game per unit bet. (payout 1 returns the bet)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PayTable {
+    /// Jacks or Better: the lowest paying hand is a pair of jacks.
+    JacksOrBetter {
+        royal_flush: u32,
+        straight_flush: u32,
+        four_of_a_kind: u32,
+        full_house: u32,
+        flush: u32,
+        straight: u32,
+        three_of_a_kind: u32,
+        two_pair: u32,
+        jacks_or_better: u32,
+    },
+    /// Deuces Wild: all deuces are wild and the lowest paying hand is three of a kind.
+    DeucesWild {
+        natural_royal_flush: u32,
+        four_deuces: u32,
+        wild_royal_flush: u32,
+        five_of_a_kind: u32,
+        straight_flush: u32,
+        four_of_a_kind: u32,
+        full_house: u32,
+        flush: u32,
+        straight: u32,
+        three_of_a_kind: u32,
+    },
+}
+
+impl PayTable {
+    /// Returns the "9/6" Jacks or Better pay table.
+    pub fn jacks_or_better_9_6() -> Self {
+        PayTable::JacksOrBetter {
+            royal_flush: 800,
+            straight_flush: 50,
+            four_of_a_kind: 25,
+            full_house: 9,
+            flush: 6,
+            straight: 4,
+            three_of_a_kind: 3,
+            two_pair: 2,
+            jacks_or_better: 1,
+        }
+    }
+
+    /// Returns the "full pay" Deuces Wild pay table.
+    pub fn deuces_wild_full_pay() -> Self {
+        PayTable::DeucesWild {
+            natural_royal_flush: 800,
+            four_deuces: 200,
+            wild_royal_flush: 25,
+            five_of_a_kind: 15,
+            straight_flush: 9,
+            four_of_a_kind: 5,
+            full_house: 3,
+            flush: 2,
+            straight: 2,
+            three_of_a_kind: 1,
+        }
+    }
+
+    /// Returns the payout of the five-card `hand`.
+    pub fn payout(&self, hand: &Hand) -> u32 {
+        match *self {
+            PayTable::JacksOrBetter {
+                royal_flush,
+                straight_flush,
+                four_of_a_kind,
+                full_house,
+                flush,
+                straight,
+                three_of_a_kind,
+                two_pair,
+                jacks_or_better,
+            } => {
+                let rank = hand.evaluate();
+                match get_hand_category(rank) {
+                    HandCategory::StraightFlush if is_royal(rank) => royal_flush,
+                    HandCategory::StraightFlush => straight_flush,
+                    HandCategory::FourOfAKind => four_of_a_kind,
+                    HandCategory::FullHouse => full_house,
+                    HandCategory::Flush => flush,
+                    HandCategory::Straight => straight,
+                    HandCategory::ThreeOfAKind => three_of_a_kind,
+                    HandCategory::TwoPair => two_pair,
+                    HandCategory::OnePair if get_pair_rank(rank) >= Some(9) => jacks_or_better,
+                    _ => 0,
+                }
+            }
+            PayTable::DeucesWild {
+                natural_royal_flush,
+                four_deuces,
+                wild_royal_flush,
+                five_of_a_kind,
+                straight_flush,
+                four_of_a_kind,
+                full_house,
+                flush,
+                straight,
+                three_of_a_kind,
+            } => {
+                let mut real_cards = *hand;
+                for deuce in 0..4 {
+                    if hand.contains(deuce) {
+                        real_cards = real_cards.remove_card(deuce);
+                    }
+                }
+                let num_deuces = hand.len() - real_cards.len();
+                if num_deuces == 4 {
+                    return four_deuces;
+                }
+                let rank = evaluate_with_wilds(&real_cards, num_deuces);
+                match get_hand_category(rank) {
+                    HandCategory::StraightFlush if is_royal(rank) && num_deuces == 0 => {
+                        natural_royal_flush
+                    }
+                    HandCategory::StraightFlush if is_royal(rank) => wild_royal_flush,
+                    HandCategory::FiveOfAKind => five_of_a_kind,
+                    HandCategory::StraightFlush => straight_flush,
+                    HandCategory::FourOfAKind => four_of_a_kind,
+                    HandCategory::FullHouse => full_house,
+                    HandCategory::Flush => flush,
+                    HandCategory::Straight => straight,
+                    HandCategory::ThreeOfAKind => three_of_a_kind,
+                    _ => 0,
+                }
+            }
+        }
+    }
+}
+
+#[inline]
+fn is_royal(hand_rank: u16) -> bool {
+    hand_rank == (8 << 12) + 9
+}
+
+/// Computes the expected payout of all 32 hold/discard choices for the dealt five-card `hand`
+/// by enumerating all possible draws.
+/// The `i`-th bit of the index means holding the `i`-th card in ascending order of card ID.
+pub fn video_poker_hold_values(pay_table: &PayTable, hand: &Hand) -> [f64; 32] {
+    assert_eq!(hand.len(), 5);
+    let cards = (0..NUMBER_OF_CARDS)
+        .filter(|&c| hand.contains(c))
+        .collect::<Vec<_>>();
+    let alive_cards = (0..NUMBER_OF_CARDS)
+        .filter(|&c| !hand.contains(c))
+        .collect::<Vec<_>>();
+
+    let mut result = [0.0; 32];
+    for (hold, value) in result.iter_mut().enumerate() {
+        let mut held = Hand::new();
+        for (i, &card) in cards.iter().enumerate() {
+            if (hold >> i) & 1 != 0 {
+                held = held.add_card(card);
+            }
+        }
+        let (sum, count) = sum_payouts(pay_table, &held, &alive_cards, 0);
+        *value = sum as f64 / count as f64;
+    }
+    result
+}
+
+fn sum_payouts(
+    pay_table: &PayTable,
+    hand: &Hand,
+    alive_cards: &[usize],
+    start: usize,
+) -> (u64, u64) {
+    if hand.len() == 5 {
+        return (pay_table.payout(hand) as u64, 1);
+    }
+    let mut result = (0, 0);
+    for i in start..alive_cards.len() {
+        let tmp = sum_payouts(
+            pay_table,
+            &hand.add_card(alive_cards[i]),
+            alive_cards,
+            i + 1,
+        );
+        result.0 += tmp.0;
+        result.1 += tmp.1;
+    }
+    result
+}
+
+/// Computes the total return of the video poker game over all 2,598,960 deals, assuming the
+/// player always chooses the hold with the highest expected payout.
+pub fn video_poker_return(pay_table: &PayTable) -> f64 {
+    let mut binomial = [[0i64; 6]; NUMBER_OF_CARDS + 1];
+    for n in 0..=NUMBER_OF_CARDS {
+        binomial[n][0] = 1;
+        for k in 1..6 {
+            binomial[n][k] = if n == 0 {
+                0
+            } else {
+                binomial[n - 1][k - 1] + binomial[n - 1][k]
+            };
+        }
+    }
+    let mut subset_offset = [0; 6];
+    for k in 1..6 {
+        subset_offset[k] = subset_offset[k - 1] + binomial[NUMBER_OF_CARDS][k - 1] as usize;
+    }
+    let subset_index = |cards: &[usize; 5], subset: usize| {
+        let mut index = 0;
+        let mut k = 0;
+        for (i, &card) in cards.iter().enumerate() {
+            if (subset >> i) & 1 != 0 {
+                k += 1;
+                index += binomial[card][k] as usize;
+            }
+        }
+        subset_offset[k] + index
+    };
+
+    // sums of payouts over all final hands that include each subset of cards
+    let mut sums = vec![0i64; subset_offset[5] + binomial[NUMBER_OF_CARDS][5] as usize];
+    let mut deals = HashMap::new();
+    for i in 0..(NUMBER_OF_CARDS - 4) {
+        let hand = Hand::new().add_card(i);
+        for j in (i + 1)..(NUMBER_OF_CARDS - 3) {
+            let hand = hand.add_card(j);
+            for k in (j + 1)..(NUMBER_OF_CARDS - 2) {
+                let hand = hand.add_card(k);
+                for m in (k + 1)..(NUMBER_OF_CARDS - 1) {
+                    let hand = hand.add_card(m);
+                    for n in (m + 1)..NUMBER_OF_CARDS {
+                        let hand = hand.add_card(n);
+                        let cards = [i, j, k, m, n];
+                        let payout = pay_table.payout(&hand) as i64;
+                        for subset in 0..32 {
+                            sums[subset_index(&cards, subset)] += payout;
+                        }
+                        let entry = deals.entry(canonical_mask(hand.get_mask()));
+                        entry.or_insert((cards, 0)).1 += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    // inclusion-exclusion: exclude final hands that contain discarded cards
+    let mut total = 0.0;
+    for (cards, weight) in deals.values() {
+        let mut best = 0.0f64;
+        for hold in 0..32usize {
+            let mut sum = 0;
+            for subset in hold..32 {
+                if (subset & hold) == hold {
+                    let sign = 1 - 2 * ((subset ^ hold).count_ones() as i64 & 1);
+                    sum += sign * sums[subset_index(cards, subset)];
+                }
+            }
+            let num_draws = binomial[NUMBER_OF_CARDS - 5][5 - hold.count_ones() as usize];
+            best = best.max(sum as f64 / num_draws as f64);
+        }
+        total += *weight as f64 * best;
+    }
+    total / binomial[NUMBER_OF_CARDS][5] as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(hand_str: &str) -> Hand {
+        hand_str.parse::<Hand>().unwrap()
+    }
+
+    #[test]
+    fn test_payout() {
+        let pay_table = PayTable::jacks_or_better_9_6();
+        assert_eq!(pay_table.payout(&parse("AsKsQsJsTs")), 800);
+        assert_eq!(pay_table.payout(&parse("KsQsJsTs9s")), 50);
+        assert_eq!(pay_table.payout(&parse("JsJdAh7c2d")), 1);
+        assert_eq!(pay_table.payout(&parse("TsTdAhKcQd")), 0);
+
+        let pay_table = PayTable::deuces_wild_full_pay();
+        assert_eq!(pay_table.payout(&parse("AsKsQsJsTs")), 800);
+        assert_eq!(pay_table.payout(&parse("2c2d2h2s7d")), 200);
+        assert_eq!(pay_table.payout(&parse("AsKs2hJsTs")), 25);
+        assert_eq!(pay_table.payout(&parse("7s7d2h2s7c")), 15);
+        assert_eq!(pay_table.payout(&parse("2s5d7h9cJd")), 0);
+        assert_eq!(pay_table.payout(&parse("2s5d5h9cJd")), 1);
+    }
+
+    #[test]
+    fn test_hold_values() {
+        let pay_table = PayTable::jacks_or_better_9_6();
+        let values = video_poker_hold_values(&pay_table, &parse("AsKsQsJsTs"));
+        assert_eq!(values[31], 800.0);
+
+        // holding four to a royal flush: 1 royal, 8 flushes, 3 straights and 12 high pairs
+        let values = video_poker_hold_values(&pay_table, &parse("AsKsQsJs2c"));
+        let expected = (800.0 + 8.0 * 6.0 + 3.0 * 4.0 + 12.0) / 47.0;
+        let hold = 0b11110; // the deuce of clubs has the lowest card ID
+        assert!((values[hold] - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_total_return() {
+        let jacks_or_better = video_poker_return(&PayTable::jacks_or_better_9_6());
+        assert!((jacks_or_better - 0.995439).abs() < 1e-6);
+        let deuces_wild = video_poker_return(&PayTable::deuces_wild_full_pay());
+        assert!((deuces_wild - 1.007620).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::hand::*;
+use assets::constants::*;
+
+/// Returns the best hand strength that the real cards in `hand` and `wild_count` wild cards
+/// (e.g., jokers) can make. Each wild card can represent any card; five of a kind ranks above
+/// straight flushes and yields `(9 << 12) + rank` (`rank` is 0 for deuces, 12 for aces).
+/// `hand.len() + wild_count` must be in the range \[5, 7\].
+pub fn evaluate_with_wilds(hand: &Hand, wild_count: usize) -> u16 {
+    if wild_count == 0 {
+        return hand.evaluate();
+    }
+
+    // wild cards used in the best five-card hand
+    let w = wild_count.min(5);
+
+    let mask = hand.get_mask();
+    let mut suits = [0; 4];
+    for (suit, rankset) in suits.iter_mut().enumerate() {
+        *rankset = (mask >> (16 * suit)) as u16 & 0x1fff;
+    }
+    let rankset = suits[0] | suits[1] | suits[2] | suits[3];
+    let mut count = [0; NUMBER_OF_RANKS];
+    for (rank, c) in count.iter_mut().enumerate() {
+        *c = suits.iter().filter(|&&s| (s >> rank) & 1 != 0).count();
+    }
+
+    // five of a kind
+    if let Some(rank) = (0..NUMBER_OF_RANKS).rev().find(|&r| count[r] + w >= 5) {
+        return ((HandCategory::FiveOfAKind as u16) << 12) + rank as u16;
+    }
+
+    // straight flush
+    let mut best = None;
+    for (suit, &s) in suits.iter().enumerate() {
+        if let Some(top) = find_straight(s, w) {
+            let hand = Hand::from_slice(&straight_cards(top, |_| suit));
+            best = best.max(Some(hand.evaluate()));
+        }
+    }
+    if let Some(rank) = best {
+        return rank;
+    }
+
+    // four of a kind
+    if let Some(quads) = (0..NUMBER_OF_RANKS).rev().find(|&r| count[r] + w >= 4) {
+        let kicker = highest_ranks(rankset & !(1 << quads), 1)[0];
+        let hand = Hand::from_slice(&[
+            card(quads, 0),
+            card(quads, 1),
+            card(quads, 2),
+            card(quads, 3),
+            card(kicker, 0),
+        ]);
+        return hand.evaluate();
+    }
+
+    // full house
+    for trips in (0..NUMBER_OF_RANKS).rev() {
+        for pair in (0..NUMBER_OF_RANKS).rev().filter(|&r| r != trips) {
+            if 3usize.saturating_sub(count[trips]) + 2usize.saturating_sub(count[pair]) <= w {
+                let hand = Hand::from_slice(&[
+                    card(trips, 0),
+                    card(trips, 1),
+                    card(trips, 2),
+                    card(pair, 0),
+                    card(pair, 1),
+                ]);
+                return hand.evaluate();
+            }
+        }
+    }
+
+    // flush
+    let mut best = None;
+    for (suit, &s) in suits.iter().enumerate() {
+        if s.count_ones() as usize + w >= 5 {
+            let mut cards = [0; 5];
+            let mut num_cards = 0;
+            let mut wilds = w;
+            for rank in (0..NUMBER_OF_RANKS).rev() {
+                if num_cards == 5 {
+                    break;
+                }
+                if (s >> rank) & 1 != 0 || wilds > 0 {
+                    if (s >> rank) & 1 == 0 {
+                        wilds -= 1;
+                    }
+                    cards[num_cards] = card(rank, suit);
+                    num_cards += 1;
+                }
+            }
+            best = best.max(Some(Hand::from_slice(&cards).evaluate()));
+        }
+    }
+    if let Some(rank) = best {
+        return rank;
+    }
+
+    // straight
+    if let Some(top) = find_straight(rankset, w) {
+        let hand = Hand::from_slice(&straight_cards(top, |i| i % 4));
+        return hand.evaluate();
+    }
+
+    // three of a kind (more wild cards would have made a better hand)
+    if let Some(trips) = (0..NUMBER_OF_RANKS).rev().find(|&r| count[r] + w >= 3) {
+        let kickers = highest_ranks(rankset & !(1 << trips), 2);
+        let hand = Hand::from_slice(&[
+            card(trips, 0),
+            card(trips, 1),
+            card(trips, 2),
+            card(kickers[0], 3),
+            card(kickers[1], 3),
+        ]);
+        return hand.evaluate();
+    }
+
+    // one pair (exactly one wild card and no pairs in real cards)
+    let pair = highest_ranks(rankset, 1)[0];
+    let kickers = highest_ranks(rankset & !(1 << pair), 3);
+    let hand = Hand::from_slice(&[
+        card(pair, 0),
+        card(pair, 1),
+        card(kickers[0], 2),
+        card(kickers[1], 3),
+        card(kickers[2], 2),
+    ]);
+    hand.evaluate()
+}
+
+#[inline]
+fn card(rank: usize, suit: usize) -> usize {
+    rank * 4 + suit
+}
+
+/// Returns the top rank of the best straight that `rankset` can make with `w` wild cards.
+/// (the top rank of the wheel is 3)
+fn find_straight(rankset: u16, w: usize) -> Option<usize> {
+    let wheel = 0b1_0000_0000_1111;
+    for top in (4..NUMBER_OF_RANKS).rev() {
+        let window = 0b11111 << (top - 4);
+        if (rankset & window).count_ones() as usize + w >= 5 {
+            return Some(top);
+        }
+    }
+    match (rankset & wheel).count_ones() as usize + w >= 5 {
+        true => Some(3),
+        false => None,
+    }
+}
+
+fn straight_cards(top: usize, suit: impl Fn(usize) -> usize) -> [usize; 5] {
+    let mut cards = [0; 5];
+    for (i, c) in cards.iter_mut().enumerate() {
+        *c = card((top + NUMBER_OF_RANKS - i) % NUMBER_OF_RANKS, suit(i));
+    }
+    cards
+}
+
+fn highest_ranks(rankset: u16, n: usize) -> [usize; 3] {
+    let mut result = [0; 3];
+    let mut rankset = rankset;
+    for r in result.iter_mut().take(n) {
+        *r = 15 - rankset.leading_zeros() as usize;
+        rankset &= !(1 << *r);
+    }
+    result
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn evaluate_with_wilds_naive(cards: &[usize], wild_count: usize) -> u16 {
+        let mut count = [0; NUMBER_OF_RANKS];
+        for card in cards {
+            count[card / 4] += 1;
+        }
+        if let Some(rank) = (0..NUMBER_OF_RANKS)
+            .rev()
+            .find(|&r| count[r] + wild_count >= 5)
+        {
+            return (9 << 12) + rank as u16;
+        }
+
+        // substitute wild cards by every combination of the remaining cards
+        let hand = Hand::from_slice(cards);
+        let alive_cards = (0..NUMBER_OF_CARDS)
+            .filter(|&c| !hand.contains(c))
+            .collect::<Vec<_>>();
+        let mut best = 0;
+        let mut stack = vec![(hand, 0, wild_count)];
+        while let Some((hand, start, wilds)) = stack.pop() {
+            if wilds == 0 {
+                best = best.max(hand.evaluate());
+                continue;
+            }
+            for (i, &card) in alive_cards.iter().enumerate().skip(start) {
+                stack.push((hand.add_card(card), i + 1, wilds - 1));
+            }
+        }
+        best
+    }
+
+    /// Checks all hands of `num_cards` real cards; when `canonical_only` is set, only one hand
+    /// per suit permutation class is checked (hand strength does not depend on the suits).
+    fn check_all_combinations(
+        cards: &mut Vec<usize>,
+        num_cards: usize,
+        wild_count: usize,
+        canonical_only: bool,
+    ) {
+        if cards.len() == num_cards {
+            let hand = Hand::from_slice(cards);
+            if canonical_only && canonical_mask(hand.get_mask()) != hand.get_mask() {
+                return;
+            }
+            assert_eq!(
+                evaluate_with_wilds(&hand, wild_count),
+                evaluate_with_wilds_naive(cards, wild_count)
+            );
+            return;
+        }
+        let start = cards.last().map_or(0, |c| c + 1);
+        for card in start..NUMBER_OF_CARDS {
+            cards.push(card);
+            check_all_combinations(cards, num_cards, wild_count, canonical_only);
+            cards.pop();
+        }
+    }
+
+    #[test]
+    fn test_wilds_5cards() {
+        for wild_count in 1..=5 {
+            check_all_combinations(&mut Vec::new(), 5 - wild_count, wild_count, false);
+        }
+    }
+
+    #[test]
+    fn test_wilds_6cards() {
+        for wild_count in 1..=5 {
+            check_all_combinations(&mut Vec::new(), 6 - wild_count, wild_count, true);
+        }
+    }
+
+    #[test]
+    fn test_wilds_7cards() {
+        for wild_count in 1..=7 {
+            check_all_combinations(&mut Vec::new(), 7 - wild_count, wild_count, true);
+        }
+    }
+
+    #[test]
+    fn test_wilds_edge_cases() {
+        let hand = "AsAh".parse::<Hand>().unwrap();
+        assert_eq!(evaluate_with_wilds(&hand, 3), (9 << 12) + 12);
+        assert_eq!(evaluate_with_wilds(&Hand::new(), 5), (9 << 12) + 12);
+        assert_eq!(
+            get_hand_category(evaluate_with_wilds(&hand, 3)),
+            HandCategory::FiveOfAKind
+        );
+        let hand = "5s4s3s2s".parse::<Hand>().unwrap();
+        assert_eq!(evaluate_with_wilds(&hand, 1), (8 << 12) + 1);
+        let hand = "AsKd9c7h".parse::<Hand>().unwrap();
+        let pair = "AsAcKd9c7h".parse::<Hand>().unwrap();
+        assert_eq!(evaluate_with_wilds(&hand, 1), pair.evaluate());
+    }
+}